@@ -0,0 +1,217 @@
+use std::{fs, io, mem, path::Path};
+
+use crate::{collector::PROC_ROOT, error::AppError, models::ThreadAffinity};
+
+/// CPUs `tid` may run on, from `sched_getaffinity(2)`.
+///
+/// Linux affinity is per thread; passing a PID reads the main thread.
+pub fn get_affinity(tid: u32) -> io::Result<Vec<usize>> {
+    let mut set: libc::cpu_set_t = unsafe { mem::zeroed() };
+    let ret = unsafe {
+        libc::sched_getaffinity(
+            tid as libc::pid_t,
+            mem::size_of::<libc::cpu_set_t>(),
+            &mut set,
+        )
+    };
+    if ret != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok((0..libc::CPU_SETSIZE as usize)
+        .filter(|&cpu| unsafe { libc::CPU_ISSET(cpu, &set) })
+        .collect())
+}
+
+/// Restrict `tid` to `cpus` with `sched_setaffinity(2)`.
+pub fn set_affinity(tid: u32, cpus: &[usize]) -> io::Result<()> {
+    let set = cpu_set(cpus)?;
+    let ret = unsafe {
+        libc::sched_setaffinity(tid as libc::pid_t, mem::size_of::<libc::cpu_set_t>(), &set)
+    };
+    if ret != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Pin `pid` to `cpus`. With `all_threads` every thread listed under
+/// `/proc/<pid>/task` is updated (like `taskset -a`); otherwise only the
+/// main thread, and threads it creates later, are affected.
+///
+/// Every thread is attempted even if some fail; the error then names the
+/// threads that kept their old affinity.
+pub fn set_process_affinity(pid: u32, cpus: &[usize], all_threads: bool) -> Result<(), AppError> {
+    if cpus.is_empty() {
+        return Err(AppError::InvalidAffinity {
+            message: "CPU list is empty".to_string(),
+        });
+    }
+
+    set_affinity(pid, cpus).map_err(|e| map_errno(pid, e))?;
+    if !all_threads {
+        return Ok(());
+    }
+
+    for_other_threads(pid, |tid| set_affinity(tid, cpus)).map_err(|(tids, err)| {
+        map_errno(pid, err).partial(&format!("threads {} were not pinned", join_tids(&tids)))
+    })
+}
+
+/// Apply `f` to every thread of `pid` but the main one, skipping threads that
+/// exit meanwhile. On failure returns the TIDs that failed and the first error.
+pub(crate) fn for_other_threads(
+    pid: u32,
+    mut f: impl FnMut(u32) -> io::Result<()>,
+) -> Result<(), (Vec<u32>, io::Error)> {
+    let mut failed = Vec::new();
+    let mut first_err = None;
+    for tid in list_tids(Path::new(PROC_ROOT), pid) {
+        if tid == pid {
+            continue;
+        }
+        match f(tid) {
+            Ok(()) => {}
+            Err(e) if e.raw_os_error() == Some(libc::ESRCH) => {}
+            Err(e) => {
+                failed.push(tid);
+                first_err.get_or_insert(e);
+            }
+        }
+    }
+    match first_err {
+        Some(err) => Err((failed, err)),
+        None => Ok(()),
+    }
+}
+
+/// `"12, 13"` for use in partial-failure messages.
+pub(crate) fn join_tids(tids: &[u32]) -> String {
+    tids.iter()
+        .map(u32::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Affinity of every thread of `pid`. Threads that vanish mid-way are skipped.
+pub fn thread_affinities(proc_root: &Path, pid: u32) -> Vec<ThreadAffinity> {
+    let task_dir = proc_root.join(pid.to_string()).join("task");
+    list_tids(proc_root, pid)
+        .into_iter()
+        .filter_map(|tid| {
+            let cpus = get_affinity(tid).ok()?;
+            let name = fs::read_to_string(task_dir.join(tid.to_string()).join("comm"))
+                .map(|s| s.trim_end().to_string())
+                .unwrap_or_default();
+            Some(ThreadAffinity { tid, name, cpus })
+        })
+        .collect()
+}
+
+/// Thread IDs under `<root>/<pid>/task`, sorted.
+pub(crate) fn list_tids(proc_root: &Path, pid: u32) -> Vec<u32> {
+    let mut tids: Vec<u32> = fs::read_dir(proc_root.join(pid.to_string()).join("task"))
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter_map(|e| e.file_name().to_str()?.parse().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    tids.sort_unstable();
+    tids
+}
+
+fn cpu_set(cpus: &[usize]) -> io::Result<libc::cpu_set_t> {
+    let mut set: libc::cpu_set_t = unsafe { mem::zeroed() };
+    for &cpu in cpus {
+        if cpu >= libc::CPU_SETSIZE as usize {
+            return Err(io::Error::from_raw_os_error(libc::EINVAL));
+        }
+        unsafe { libc::CPU_SET(cpu, &mut set) };
+    }
+    Ok(set)
+}
+
+fn map_errno(pid: u32, err: io::Error) -> AppError {
+    match err.raw_os_error() {
+        Some(libc::EINVAL) => AppError::InvalidAffinity {
+            message: "CPU list contains no usable CPU".to_string(),
+        },
+        Some(libc::EPERM) => AppError::PermissionDenied {
+            pid,
+            message: "Insufficient permissions to change this process's CPU affinity".to_string(),
+        },
+        Some(libc::ESRCH) => AppError::NotFound { pid },
+        _ => AppError::OsError {
+            message: err.to_string(),
+        },
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    #[test]
+    fn test_pin_child_to_one_cpu() {
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        let pid = child.id();
+        let allowed = get_affinity(pid).unwrap();
+        assert!(!allowed.is_empty());
+
+        let target = *allowed.last().unwrap();
+        set_process_affinity(pid, &[target], true).unwrap();
+        assert_eq!(get_affinity(pid).unwrap(), vec![target]);
+
+        let threads = thread_affinities(Path::new(PROC_ROOT), pid);
+        assert_eq!(threads.len(), 1);
+        assert_eq!(threads[0].tid, pid);
+        assert_eq!(threads[0].name, "sleep");
+        assert_eq!(threads[0].cpus, vec![target]);
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+
+    #[test]
+    fn test_rejects_unusable_cpu_lists() {
+        let pid = std::process::id();
+        assert!(matches!(
+            set_process_affinity(pid, &[], false),
+            Err(AppError::InvalidAffinity { .. })
+        ));
+        assert!(matches!(
+            set_process_affinity(pid, &[libc::CPU_SETSIZE as usize], false),
+            Err(AppError::InvalidAffinity { .. })
+        ));
+    }
+
+    #[test]
+    fn test_other_threads_failures_are_collected() {
+        // The test harness runs this on a worker thread, so there is at least
+        // one thread besides the main one.
+        let pid = std::process::id();
+        let mut seen = Vec::new();
+        let (failed, err) = for_other_threads(pid, |tid| {
+            seen.push(tid);
+            Err(io::Error::from_raw_os_error(libc::EPERM))
+        })
+        .unwrap_err();
+        assert!(!seen.is_empty());
+        assert!(!seen.contains(&pid));
+        assert_eq!(failed, seen);
+        assert_eq!(err.raw_os_error(), Some(libc::EPERM));
+
+        let message = match map_errno(pid, err).partial("threads 1, 2 were not pinned") {
+            AppError::PermissionDenied { message, .. } => message,
+            other => panic!("unexpected error: {other:?}"),
+        };
+        assert!(message.ends_with("; threads 1, 2 were not pinned"));
+        assert_eq!(join_tids(&[12, 13]), "12, 13");
+    }
+}
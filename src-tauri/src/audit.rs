@@ -0,0 +1,216 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::models::{AuditEntry, AuditQuery};
+
+/// Size at which the active log file is rotated.
+pub const AUDIT_MAX_BYTES: u64 = 1024 * 1024;
+/// Rotated files kept next to the active one (`audit.jsonl.1` … `.N`).
+pub const AUDIT_MAX_ROTATED: usize = 5;
+/// Entries returned by `list_audit_log` when the query has no limit.
+pub const DEFAULT_AUDIT_LIMIT: usize = 500;
+
+/// Append-only JSON-lines log of actions taken against processes.
+///
+/// `audit.jsonl` is rotated to `audit.jsonl.1` once it would grow past
+/// `max_bytes`; older files shift up and the oldest is dropped.
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    path: PathBuf,
+    max_bytes: u64,
+    max_rotated: usize,
+}
+
+impl AuditLog {
+    /// Log stored as `<dir>/audit.jsonl`. The directory is created on first write.
+    pub fn new(dir: &Path) -> Self {
+        Self::with_limits(dir.join("audit.jsonl"), AUDIT_MAX_BYTES, AUDIT_MAX_ROTATED)
+    }
+
+    pub fn with_limits(path: PathBuf, max_bytes: u64, max_rotated: usize) -> Self {
+        Self {
+            path,
+            max_bytes,
+            max_rotated,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&self, entry: &AuditEntry) -> io::Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let len = fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0);
+        if len > 0 && len + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(&line)
+    }
+
+    /// Entries matching `query`, newest first. Unparseable lines are skipped.
+    pub fn read(&self, query: &AuditQuery) -> io::Result<Vec<AuditEntry>> {
+        let limit = query.limit.unwrap_or(DEFAULT_AUDIT_LIMIT);
+        let mut result = Vec::new();
+
+        // Newest file first; lines within a file are oldest first.
+        for index in 0..=self.max_rotated {
+            let file = match File::open(self.rotated_path(index)) {
+                Ok(f) => f,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+            let mut entries: Vec<AuditEntry> = BufReader::new(file)
+                .lines()
+                .map_while(Result::ok)
+                .filter_map(|line| serde_json::from_str(&line).ok())
+                .filter(|e| query.matches(e))
+                .collect();
+            entries.reverse();
+            result.extend(entries);
+            if result.len() >= limit {
+                break;
+            }
+        }
+
+        result.truncate(limit);
+        Ok(result)
+    }
+
+    fn rotate(&self) -> io::Result<()> {
+        if self.max_rotated == 0 {
+            return fs::remove_file(&self.path);
+        }
+        for index in (1..self.max_rotated).rev() {
+            let from = self.rotated_path(index);
+            if from.exists() {
+                fs::rename(&from, self.rotated_path(index + 1))?;
+            }
+        }
+        fs::rename(&self.path, self.rotated_path(1))
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        if index == 0 {
+            return self.path.clone();
+        }
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(format!(".{index}"));
+        PathBuf::from(name)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::AppError, models::AuditOutcome};
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(tag: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("pm-audit-{}-{}", tag, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            Self(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn entry(timestamp_ms: u64, pid: u32, action: &str) -> AuditEntry {
+        AuditEntry {
+            timestamp_ms,
+            actor: "alice".to_string(),
+            pid,
+            start_time: Some(1000),
+            name: Some("worker".to_string()),
+            cmd: vec!["worker".to_string(), "--once".to_string()],
+            action: action.to_string(),
+            detail: "mode=Kill".to_string(),
+            outcome: AuditOutcome::Success,
+        }
+    }
+
+    #[test]
+    fn test_append_and_query() {
+        let dir = TempDir::new("query");
+        let log = AuditLog::new(&dir.0);
+        log.append(&entry(1, 10, "kill_process")).unwrap();
+        log.append(&entry(2, 20, "set_priority")).unwrap();
+        let mut failed = entry(3, 10, "kill_process");
+        failed.outcome = AuditOutcome::Failed {
+            error: AppError::NotFound { pid: 10 },
+        };
+        log.append(&failed).unwrap();
+
+        let all = log.read(&AuditQuery::default()).unwrap();
+        let times: Vec<u64> = all.iter().map(|e| e.timestamp_ms).collect();
+        assert_eq!(times, vec![3, 2, 1]);
+        assert!(matches!(
+            all[0].outcome,
+            AuditOutcome::Failed {
+                error: AppError::NotFound { pid: 10 }
+            }
+        ));
+
+        let query = AuditQuery {
+            pid: Some(10),
+            since_ms: Some(2),
+            ..Default::default()
+        };
+        let found = log.read(&query).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].timestamp_ms, 3);
+    }
+
+    #[test]
+    fn test_rotation_keeps_newest() {
+        let dir = TempDir::new("rotate");
+        let line_len = serde_json::to_vec(&entry(1, 1, "kill_process"))
+            .unwrap()
+            .len() as u64
+            + 1;
+        // Two entries per file, two rotated files.
+        let log = AuditLog::with_limits(dir.0.join("audit.jsonl"), line_len * 2, 2);
+        for t in 1..=9 {
+            log.append(&entry(t, 1, "kill_process")).unwrap();
+        }
+
+        assert!(dir.0.join("audit.jsonl.2").exists());
+        assert!(!dir.0.join("audit.jsonl.3").exists());
+        let times: Vec<u64> = log
+            .read(&AuditQuery::default())
+            .unwrap()
+            .iter()
+            .map(|e| e.timestamp_ms)
+            .collect();
+        assert_eq!(times, vec![9, 8, 7, 6, 5]);
+
+        let limited = AuditQuery {
+            limit: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(log.read(&limited).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,208 @@
+use std::{fs, ops::RangeInclusive, path::Path};
+
+use crate::models::ProcessKind;
+
+/// First UID of a regular login account when `login.defs` does not say.
+#[cfg(target_os = "macos")]
+const DEFAULT_UID_MIN: u32 = 501;
+#[cfg(not(target_os = "macos"))]
+const DEFAULT_UID_MIN: u32 = 1000;
+/// Last UID of a regular login account when `login.defs` does not say.
+const DEFAULT_UID_MAX: u32 = 60000;
+
+/// PID of `kthreadd`, the parent of every other Linux kernel thread.
+pub const KTHREADD_PID: u32 = 2;
+
+/// cgroups systemd places services, the init scope and VMs/containers in.
+const SYSTEM_CGROUPS: &[&str] = &["/system.slice", "/init.scope", "/machine.slice"];
+
+/// Built-in Windows service accounts, which have no UID.
+const WINDOWS_SYSTEM_ACCOUNTS: &[&str] = &[
+    "SYSTEM",
+    "LOCAL SERVICE",
+    "NETWORK SERVICE",
+    "NT AUTHORITY\\SYSTEM",
+    "NT AUTHORITY\\LOCAL SERVICE",
+    "NT AUTHORITY\\NETWORK SERVICE",
+];
+
+/// What the classifier looks at for one process.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProcessTraits<'a> {
+    pub uid: Option<u32>,
+    pub user: Option<&'a str>,
+    pub kernel_thread: bool,
+    /// Unified cgroup path, e.g. `/system.slice/sshd.service`
+    pub cgroup: Option<&'a str>,
+}
+
+/// Sorts processes into kernel threads, system services and user processes.
+///
+/// Kernel threads come first, then anything in a system cgroup slice; the
+/// rest is decided by whether the UID falls in the login-account range from
+/// `/etc/login.defs` (`UID_MIN`..=`UID_MAX`).
+#[derive(Debug, Clone)]
+pub struct Classifier {
+    user_uids: RangeInclusive<u32>,
+}
+
+impl Classifier {
+    pub fn new() -> Self {
+        Self::from_login_defs(Path::new("/etc/login.defs"))
+    }
+
+    /// Read the login-account UID range from a `login.defs` file, falling back
+    /// to the platform defaults for missing keys or a missing file.
+    pub fn from_login_defs(path: &Path) -> Self {
+        let content = fs::read_to_string(path).unwrap_or_default();
+        Self::with_user_uids(parse_login_defs(&content))
+    }
+
+    pub fn with_user_uids(user_uids: RangeInclusive<u32>) -> Self {
+        Self { user_uids }
+    }
+
+    pub fn classify(&self, p: &ProcessTraits) -> ProcessKind {
+        if p.kernel_thread {
+            return ProcessKind::Kernel;
+        }
+        if p.cgroup.is_some_and(is_system_cgroup) {
+            return ProcessKind::System;
+        }
+        match (p.uid, p.user) {
+            (Some(uid), _) if self.user_uids.contains(&uid) => ProcessKind::User,
+            (Some(_), _) => ProcessKind::System,
+            (None, Some(user)) if is_windows_system_account(user) => ProcessKind::System,
+            (None, Some(_)) => ProcessKind::User,
+            (None, None) => ProcessKind::Unknown,
+        }
+    }
+}
+
+impl Default for Classifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Linux kernel threads have no executable and are `kthreadd` or its children.
+pub fn is_kernel_thread(pid: u32, parent_pid: Option<u32>, exe: Option<&str>) -> bool {
+    exe.is_none() && (pid == KTHREADD_PID || parent_pid == Some(KTHREADD_PID))
+}
+
+fn is_system_cgroup(cgroup: &str) -> bool {
+    SYSTEM_CGROUPS.iter().any(|prefix| {
+        cgroup
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    })
+}
+
+fn is_windows_system_account(user: &str) -> bool {
+    WINDOWS_SYSTEM_ACCOUNTS
+        .iter()
+        .any(|account| account.eq_ignore_ascii_case(user))
+}
+
+/// `UID_MIN` and `UID_MAX` from `login.defs` content.
+fn parse_login_defs(content: &str) -> RangeInclusive<u32> {
+    let value = |key: &str| {
+        content.lines().find_map(|line| {
+            let mut parts = line.split_whitespace();
+            if parts.next()? != key {
+                return None;
+            }
+            parts.next()?.parse().ok()
+        })
+    };
+    value("UID_MIN").unwrap_or(DEFAULT_UID_MIN)..=value("UID_MAX").unwrap_or(DEFAULT_UID_MAX)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_login_defs() {
+        let content = "# comment UID_MIN 1\nUID_MIN\t\t 500\nUID_MAX   29999\nSYS_UID_MIN 100\n";
+        assert_eq!(parse_login_defs(content), 500..=29999);
+        assert_eq!(parse_login_defs("UID_MAX 70000\n"), DEFAULT_UID_MIN..=70000);
+        assert_eq!(parse_login_defs(""), DEFAULT_UID_MIN..=DEFAULT_UID_MAX);
+    }
+
+    #[test]
+    fn test_classify() {
+        let classifier = Classifier::with_user_uids(1000..=60000);
+        let kind = |traits: ProcessTraits| classifier.classify(&traits);
+
+        // A daemon account below UID_MIN is a system process whatever its PID.
+        assert_eq!(
+            kind(ProcessTraits {
+                uid: Some(991),
+                user: Some("systemd-resolve"),
+                ..Default::default()
+            }),
+            ProcessKind::System
+        );
+        assert_eq!(
+            kind(ProcessTraits {
+                uid: Some(1000),
+                cgroup: Some("/user.slice/user-1000.slice/session-2.scope"),
+                ..Default::default()
+            }),
+            ProcessKind::User
+        );
+        // Services run as a regular user are still system processes.
+        assert_eq!(
+            kind(ProcessTraits {
+                uid: Some(1000),
+                cgroup: Some("/system.slice/myapp.service"),
+                ..Default::default()
+            }),
+            ProcessKind::System
+        );
+        assert_eq!(
+            kind(ProcessTraits {
+                uid: Some(1000),
+                cgroup: Some("/system.slicer/x"),
+                ..Default::default()
+            }),
+            ProcessKind::User
+        );
+        assert_eq!(
+            kind(ProcessTraits {
+                uid: Some(65534),
+                ..Default::default()
+            }),
+            ProcessKind::System
+        );
+        assert_eq!(
+            kind(ProcessTraits {
+                uid: Some(0),
+                kernel_thread: true,
+                ..Default::default()
+            }),
+            ProcessKind::Kernel
+        );
+        assert_eq!(
+            kind(ProcessTraits {
+                user: Some("Local Service"),
+                ..Default::default()
+            }),
+            ProcessKind::System
+        );
+        assert_eq!(kind(ProcessTraits::default()), ProcessKind::Unknown);
+    }
+
+    #[test]
+    fn test_is_kernel_thread() {
+        assert!(is_kernel_thread(2, None, None));
+        assert!(is_kernel_thread(57, Some(2), None));
+        assert!(!is_kernel_thread(57, Some(2), Some("/usr/bin/x")));
+        assert!(!is_kernel_thread(900, Some(1), None));
+    }
+}
@@ -0,0 +1,114 @@
+use std::{
+    fs,
+    path::PathBuf,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use super::ProcfsCollector;
+
+/// Builds a throwaway fake `/proc` tree under the system temp dir.
+pub struct FakeProc {
+    root: PathBuf,
+}
+
+impl FakeProc {
+    pub fn new() -> Self {
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+        let root = std::env::temp_dir().join(format!(
+            "pm-fake-proc-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(
+            root.join("passwd"),
+            "root:x:0:0::/root:/bin/sh\nalice:x:1000:1000::/home/alice:/bin/sh\n",
+        )
+        .unwrap();
+        fs::write(root.join("login.defs"), "UID_MIN 1000\nUID_MAX 60000\n").unwrap();
+        let fake = Self { root };
+        fake.set_cpu_total(1000);
+        fake
+    }
+
+    pub fn proc_root(&self) -> PathBuf {
+        self.root.join("proc")
+    }
+
+    /// Write `/proc/stat` with two CPUs and the given aggregate tick count.
+    pub fn set_cpu_total(&self, total: u64) {
+        fs::create_dir_all(self.proc_root()).unwrap();
+        fs::write(
+            self.proc_root().join("stat"),
+            format!(
+                "cpu  {total} 0 0 0 0 0 0 0 0 0\ncpu0 0 0 0 0\ncpu1 0 0 0 0\nbtime 1700000000\n"
+            ),
+        )
+        .unwrap();
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn add(
+        &self,
+        pid: u32,
+        comm: &str,
+        state: char,
+        ppid: u32,
+        uid: u32,
+        cpu_ticks: u64,
+        start: u64,
+        rss_pages: u64,
+    ) {
+        let dir = self.proc_root().join(pid.to_string());
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("stat"),
+            format!("{pid} ({comm}) {state} {ppid} {pid} {pid} 0 -1 4194560 0 0 0 0 {cpu_ticks} 0 0 0 20 0 3 0 {start} 8192000 {rss_pages} 0\n"),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("status"),
+            format!("Name:\t{comm}\nUid:\t{uid}\t{uid}\t{uid}\t{uid}\n"),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("statm"),
+            format!("2000 {rss_pages} 100 10 0 500 0\n"),
+        )
+        .unwrap();
+        fs::write(dir.join("cmdline"), format!("/usr/bin/{comm}\0--flag\0")).unwrap();
+        fs::write(
+            dir.join("io"),
+            "rchar: 10\nwchar: 20\nread_bytes: 4096\nwrite_bytes: 8192\n",
+        )
+        .unwrap();
+        fs::write(dir.join("oom_score"), "666\n").unwrap();
+        fs::write(dir.join("oom_score_adj"), "-100\n").unwrap();
+        std::os::unix::fs::symlink(format!("/usr/bin/{comm}"), dir.join("exe")).unwrap();
+    }
+
+    /// Rewrite a process's utime while leaving everything else intact.
+    pub fn add_ticks(&self, pid: u32, utime: u64) {
+        let path = self.proc_root().join(pid.to_string()).join("stat");
+        let stat = fs::read_to_string(&path).unwrap();
+        let close = stat.rfind(')').unwrap();
+        let mut fields: Vec<String> = stat[close + 1..]
+            .split_whitespace()
+            .map(String::from)
+            .collect();
+        fields[14 - 3] = utime.to_string();
+        fs::write(&path, format!("{} {}\n", &stat[..=close], fields.join(" "))).unwrap();
+    }
+
+    pub fn collector(&self) -> ProcfsCollector {
+        ProcfsCollector::with_root(self.proc_root())
+            .with_passwd_file(self.root.join("passwd"))
+            .with_login_defs(&self.root.join("login.defs"))
+    }
+}
+
+impl Drop for FakeProc {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
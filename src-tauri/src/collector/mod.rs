@@ -1,3 +1,112 @@
+#[cfg(all(test, target_os = "linux"))]
+pub(crate) mod fake_proc;
+#[cfg(target_os = "linux")]
+pub mod procfs_collector;
 pub mod sysinfo_collector;
+pub mod system_collector;
 
+use std::collections::HashMap;
+
+use tracing::warn;
+
+use crate::models::ProcessDto;
+
+#[cfg(target_os = "linux")]
+pub use procfs_collector::ProcfsCollector;
 pub use sysinfo_collector::SysinfoCollector;
+pub use system_collector::SystemCollector;
+
+/// Procfs mount point read unless a different root is configured.
+#[cfg(target_os = "linux")]
+pub const PROC_ROOT: &str = "/proc";
+
+/// Environment variable used to pick a collector backend at startup.
+pub const COLLECTOR_ENV_VAR: &str = "PM_COLLECTOR";
+
+/// Source of process snapshots consumed by the updater.
+///
+/// Implementations are driven from the updater task, so they must be `Send`.
+pub trait ProcessCollector: Send {
+    /// Refresh process data and return a map of PID → DTO.
+    fn collect(&mut self) -> HashMap<u32, ProcessDto>;
+}
+
+/// Collector backends selectable at startup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CollectorKind {
+    #[default]
+    Sysinfo,
+    /// Reads `/proc` directly (Linux only).
+    #[cfg(target_os = "linux")]
+    Procfs,
+}
+
+impl CollectorKind {
+    /// Parse a backend name (case-insensitive). Returns `None` for unknown names.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "sysinfo" => Some(Self::Sysinfo),
+            #[cfg(target_os = "linux")]
+            "procfs" => Some(Self::Procfs),
+            _ => None,
+        }
+    }
+
+    /// Read the backend from `PM_COLLECTOR`, falling back to the default.
+    pub fn from_env() -> Self {
+        match std::env::var(COLLECTOR_ENV_VAR) {
+            Ok(name) => Self::parse(&name).unwrap_or_else(|| {
+                warn!("Unknown collector '{}', falling back to default", name);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Construct the collector for this backend.
+    pub fn build(self) -> Box<dyn ProcessCollector> {
+        match self {
+            Self::Sysinfo => Box::new(SysinfoCollector::new()),
+            #[cfg(target_os = "linux")]
+            Self::Procfs => Box::new(ProcfsCollector::new()),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_collector_kind() {
+        assert_eq!(
+            CollectorKind::parse("sysinfo"),
+            Some(CollectorKind::Sysinfo)
+        );
+        assert_eq!(
+            CollectorKind::parse(" SysInfo "),
+            Some(CollectorKind::Sysinfo)
+        );
+        assert_eq!(CollectorKind::parse("bogus"), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_boxed_collector_collects() {
+        let fake = fake_proc::FakeProc::new();
+        fake.add(1, "init", 'S', 0, 0, 0, 10, 100);
+        fake.add(1000, "bash", 'R', 1, 1000, 0, 500, 250);
+
+        let mut collector: Box<dyn ProcessCollector> = Box::new(fake.collector());
+        let procs = collector.collect();
+        let mut pids: Vec<u32> = procs.keys().copied().collect();
+        pids.sort_unstable();
+        assert_eq!(pids, vec![1, 1000]);
+        assert!(procs[&1000].memory_bytes > 0);
+        assert!(!procs[&1000].cmd.is_empty());
+    }
+}
@@ -0,0 +1,986 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use parking_lot::Mutex;
+use tracing::trace;
+
+use super::{ProcessCollector, SysinfoCollector};
+use crate::{
+    classify::{is_kernel_thread, Classifier, ProcessTraits},
+    models::{
+        DetailField, ProcessDetails, ProcessDto, ProcessStatus as DtoStatus, ResourceLimit,
+        RlimitResource, SchedulingPolicy, SpawnOptions,
+    },
+    priority::policy_from_raw,
+};
+
+/// Reads process data straight from a procfs tree.
+///
+/// The root defaults to `/proc` but can point at any directory laid out the
+/// same way, which is how the fixture-based tests drive it.
+pub struct ProcfsCollector {
+    proc_root: PathBuf,
+    passwd_path: PathBuf,
+    classifier: Classifier,
+    clock_ticks: u64,
+    page_size: u64,
+    boot_time: u64,
+    /// CPU ticks per PID from the previous tick, keyed with the start time so
+    /// a recycled PID does not inherit its predecessor's counters.
+    prev_cpu: HashMap<u32, (u64, u64)>,
+    prev_total_ticks: Option<u64>,
+}
+
+/// Raw per-process record parsed from `<root>/<pid>/`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProcfsProcess {
+    pub pid: u32,
+    /// `comm` from `stat` (truncated to 15 bytes by the kernel)
+    pub name: String,
+    /// Single-letter state from `stat` (R, S, D, T, Z, ...)
+    pub state: char,
+    pub ppid: u32,
+    pub utime_ticks: u64,
+    pub stime_ticks: u64,
+    pub num_threads: u32,
+    pub nice: i32,
+    /// Raw `SCHED_*` policy (None on kernels whose `stat` predates the field)
+    pub policy: Option<i32>,
+    /// Start time in clock ticks since boot
+    pub start_ticks: u64,
+    pub vsize_bytes: u64,
+    /// Resident set size from `statm`
+    pub rss_bytes: u64,
+    /// Real UID from `status`
+    pub uid: Option<u32>,
+    pub cmdline: Vec<String>,
+    /// Target of the `exe` link (None for kernel threads or restricted)
+    pub exe: Option<String>,
+    /// Target of the `cwd` link (None when restricted)
+    pub cwd: Option<String>,
+    /// Unified cgroup path (None when unreadable)
+    pub cgroup: Option<String>,
+    /// Storage I/O counters (None when `io` is unreadable)
+    pub io: Option<ProcfsIo>,
+    pub oom_score: Option<u32>,
+    pub oom_score_adj: Option<i32>,
+}
+
+/// Byte counters from `<root>/<pid>/io`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProcfsIo {
+    pub read_bytes: u64,
+    pub write_bytes: u64,
+}
+
+impl ProcfsCollector {
+    pub fn new() -> Self {
+        Self::with_root(super::PROC_ROOT)
+    }
+
+    /// Create a collector reading from an arbitrary procfs root.
+    pub fn with_root(proc_root: impl Into<PathBuf>) -> Self {
+        let proc_root = proc_root.into();
+        let boot_time = boot_time(&proc_root).unwrap_or(0);
+        Self {
+            proc_root,
+            passwd_path: PathBuf::from("/etc/passwd"),
+            classifier: Classifier::new(),
+            clock_ticks: sysconf(libc::_SC_CLK_TCK).unwrap_or(100),
+            page_size: sysconf(libc::_SC_PAGESIZE).unwrap_or(4096),
+            boot_time,
+            prev_cpu: HashMap::new(),
+            prev_total_ticks: None,
+        }
+    }
+
+    /// Resolve usernames from a different passwd file (default `/etc/passwd`).
+    pub fn with_passwd_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.passwd_path = path.into();
+        self
+    }
+
+    /// Take the login-account UID range from a different `login.defs`
+    /// (default `/etc/login.defs`).
+    pub fn with_login_defs(mut self, path: &Path) -> Self {
+        self.classifier = Classifier::from_login_defs(path);
+        self
+    }
+
+    pub fn proc_root(&self) -> &Path {
+        &self.proc_root
+    }
+
+    /// Parse every file we use for one PID. Returns `None` if the process is
+    /// gone or its `stat` file cannot be read.
+    pub fn read_process(&self, pid: u32) -> Option<ProcfsProcess> {
+        let dir = self.proc_root.join(pid.to_string());
+        let stat = parse_stat(&fs::read_to_string(dir.join("stat")).ok()?)?;
+
+        let uid = fs::read_to_string(dir.join("status"))
+            .ok()
+            .and_then(|s| parse_status_uid(&s));
+        let rss_pages = fs::read_to_string(dir.join("statm"))
+            .ok()
+            .and_then(|s| parse_statm_rss(&s))
+            .unwrap_or(0);
+        let cmdline = fs::read(dir.join("cmdline"))
+            .map(|b| parse_cmdline(&b))
+            .unwrap_or_default();
+        let exe = fs::read_link(dir.join("exe"))
+            .ok()
+            .map(|p| p.to_string_lossy().to_string());
+        let cwd = fs::read_link(dir.join("cwd"))
+            .ok()
+            .map(|p| p.to_string_lossy().to_string());
+        let cgroup = read_cgroup(&self.proc_root, pid);
+        let io = fs::read_to_string(dir.join("io"))
+            .ok()
+            .and_then(|s| parse_io(&s));
+        let (oom_score, oom_score_adj) = read_oom(&self.proc_root, pid);
+
+        Some(ProcfsProcess {
+            pid,
+            name: stat.comm,
+            state: stat.state,
+            ppid: stat.ppid,
+            utime_ticks: stat.utime,
+            stime_ticks: stat.stime,
+            num_threads: stat.num_threads,
+            nice: stat.nice,
+            policy: stat.policy,
+            start_ticks: stat.starttime,
+            vsize_bytes: stat.vsize,
+            rss_bytes: rss_pages * self.page_size,
+            uid,
+            cmdline,
+            exe,
+            cwd,
+            cgroup,
+            io,
+            oom_score,
+            oom_score_adj,
+        })
+    }
+
+    /// PIDs currently listed under the proc root.
+    fn list_pids(&self) -> Vec<u32> {
+        fs::read_dir(&self.proc_root)
+            .map(|entries| {
+                entries
+                    .filter_map(Result::ok)
+                    .filter_map(|e| e.file_name().to_str()?.parse().ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn map_process(
+        &self,
+        p: ProcfsProcess,
+        cpu_percent: f32,
+        users: &HashMap<u32, String>,
+        current_user: &str,
+    ) -> ProcessDto {
+        let user = p.uid.and_then(|uid| users.get(&uid).cloned());
+
+        let needs_elevation = user
+            .as_deref()
+            .map(|u| u != current_user && !u.is_empty())
+            .unwrap_or(false);
+
+        let parent_pid = (p.ppid != 0).then_some(p.ppid);
+        let kind = self.classifier.classify(&ProcessTraits {
+            uid: p.uid,
+            user: user.as_deref(),
+            kernel_thread: is_kernel_thread(p.pid, parent_pid, p.exe.as_deref()),
+            cgroup: p.cgroup.as_deref(),
+        });
+
+        ProcessDto {
+            pid: p.pid,
+            name: p.name,
+            status: map_state(p.state),
+            status_since_ms: None,
+            cpu_percent,
+            memory_bytes: p.rss_bytes,
+            user,
+            path: p.exe,
+            cwd: p.cwd,
+            parent_pid,
+            start_time: Some(self.boot_time + p.start_ticks / self.clock_ticks),
+            needs_elevation,
+            kind,
+            cmd: p.cmdline,
+            threads: Some(p.num_threads),
+            disk_read_bytes: p.io.map(|io| io.read_bytes),
+            disk_written_bytes: p.io.map(|io| io.write_bytes),
+            nice: Some(p.nice),
+            scheduling_policy: p.policy.and_then(policy_from_raw),
+            oom_score: p.oom_score,
+            oom_score_adj: p.oom_score_adj,
+            launched_by_app: false,
+        }
+    }
+}
+
+impl ProcessCollector for ProcfsCollector {
+    fn collect(&mut self) -> HashMap<u32, ProcessDto> {
+        let users = fs::read_to_string(&self.passwd_path)
+            .map(|s| parse_passwd(&s))
+            .unwrap_or_default();
+        let current_user = SysinfoCollector::get_current_username();
+
+        let cpu_times = fs::read_to_string(self.proc_root.join("stat"))
+            .ok()
+            .and_then(|s| parse_cpu_times(&s));
+        // Ticks elapsed on one core since the previous collection.
+        let elapsed_per_core = match (cpu_times, self.prev_total_ticks) {
+            (Some((total, cpus)), Some(prev)) if total > prev && cpus > 0 => {
+                Some((total - prev) as f32 / cpus as f32)
+            }
+            _ => None,
+        };
+        let max_percent = cpu_times.map(|(_, cpus)| cpus.max(1) as f32 * 100.0);
+
+        let mut prev_cpu = HashMap::new();
+        let mut result = HashMap::new();
+
+        for pid in self.list_pids() {
+            let Some(p) = self.read_process(pid) else {
+                continue;
+            };
+
+            let ticks = p.utime_ticks + p.stime_ticks;
+            let cpu_percent = match (elapsed_per_core, self.prev_cpu.get(&pid)) {
+                (Some(elapsed), Some(&(start, prev_ticks))) if start == p.start_ticks => {
+                    let pct = ticks.saturating_sub(prev_ticks) as f32 / elapsed * 100.0;
+                    pct.min(max_percent.unwrap_or(f32::MAX))
+                }
+                _ => 0.0,
+            };
+            prev_cpu.insert(pid, (p.start_ticks, ticks));
+
+            let dto = self.map_process(p, cpu_percent, &users, &current_user);
+            result.insert(pid, dto);
+        }
+
+        self.prev_cpu = prev_cpu;
+        self.prev_total_ticks = cpu_times.map(|(total, _)| total);
+
+        trace!("Collected {} processes from procfs", result.len());
+        result
+    }
+}
+
+impl Default for ProcfsCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Fill the on-demand detail fields for `dto` from `<root>/<pid>/`.
+///
+/// Each file is read independently so one denied read does not hide the
+/// rest; denied fields are listed in `ProcessDetails::restricted`.
+pub fn read_details(proc_root: &Path, dto: ProcessDto) -> ProcessDetails {
+    let dir = proc_root.join(dto.pid.to_string());
+    let mut restricted = Vec::new();
+
+    let stat = fs::read_to_string(dir.join("stat"))
+        .ok()
+        .and_then(|s| parse_stat(&s));
+
+    let status = read_field(
+        &[DetailField::Threads, DetailField::VirtualMemoryBytes],
+        &mut restricted,
+        || fs::read_to_string(dir.join("status")).map(Some),
+    );
+    let io = read_field(
+        &[DetailField::DiskReadBytes, DetailField::DiskWrittenBytes],
+        &mut restricted,
+        || fs::read_to_string(dir.join("io")).map(|s| parse_io(&s)),
+    );
+    let open_files_count = read_field(&[DetailField::OpenFilesCount], &mut restricted, || {
+        fs::read_dir(dir.join("fd")).map(|entries| Some(entries.count()))
+    });
+    let environment = read_field(&[DetailField::Environment], &mut restricted, || {
+        fs::read(dir.join("environ")).map(|b| Some(parse_cmdline(&b)))
+    });
+    let resource_limits = read_field(&[DetailField::ResourceLimits], &mut restricted, || {
+        fs::read_to_string(dir.join("limits")).map(|s| Some(parse_limits(&s)))
+    });
+
+    ProcessDetails {
+        dto,
+        threads: status.as_deref().and_then(|s| status_value(s, "Threads:")),
+        virtual_memory_bytes: status
+            .as_deref()
+            .and_then(|s| status_value::<u64>(s, "VmSize:"))
+            .map(|kb| kb * 1024),
+        disk_read_bytes: io.map(|io| io.read_bytes),
+        disk_written_bytes: io.map(|io| io.write_bytes),
+        open_files_count,
+        environment,
+        realtime_priority: stat.and_then(|s| s.rt_priority),
+        resource_limits,
+        io_priority: None,
+        cpu_affinity: None,
+        thread_affinity: None,
+        restricted,
+    }
+}
+
+/// State letter and start time (clock ticks since boot) from `<root>/<pid>/stat`.
+/// The pair identifies a process across PID reuse.
+pub fn read_state(proc_root: &Path, pid: u32) -> Option<(char, u64)> {
+    let stat = parse_stat(&fs::read_to_string(proc_root.join(pid.to_string()).join("stat")).ok()?)?;
+    Some((stat.state, stat.starttime))
+}
+
+/// Start time of `pid` in Unix seconds, computed the same way as for `ProcessDto::start_time`.
+pub fn read_start_time(proc_root: &Path, pid: u32) -> Option<u64> {
+    let (_, start_ticks) = read_state(proc_root, pid)?;
+    start_ticks_to_unix(proc_root, start_ticks)
+}
+
+/// Nice value, scheduling policy and start time (Unix seconds) of `pid`, from
+/// a single read of `<root>/<pid>/stat`.
+pub fn read_scheduling(proc_root: &Path, pid: u32) -> Option<(i32, Option<SchedulingPolicy>, u64)> {
+    let stat = parse_stat(&fs::read_to_string(proc_root.join(pid.to_string()).join("stat")).ok()?)?;
+    let start_time = start_ticks_to_unix(proc_root, stat.starttime)?;
+    Some((stat.nice, stat.policy.and_then(policy_from_raw), start_time))
+}
+
+/// Convert a raw `starttime` (clock ticks since boot) to Unix seconds.
+pub fn start_ticks_to_unix(proc_root: &Path, start_ticks: u64) -> Option<u64> {
+    let clock_ticks = sysconf(libc::_SC_CLK_TCK).unwrap_or(100);
+    Some(boot_time(proc_root)? + start_ticks / clock_ticks)
+}
+
+/// `btime` of `proc_root`, read once per root. The kernel derives it from the
+/// wall clock, so re-reading it after a clock step would shift every start
+/// time and break comparisons with earlier snapshots.
+fn boot_time(proc_root: &Path) -> Option<u64> {
+    static BOOT_TIMES: OnceLock<Mutex<HashMap<PathBuf, u64>>> = OnceLock::new();
+    let mut cache = BOOT_TIMES.get_or_init(Default::default).lock();
+    if let Some(boot_time) = cache.get(proc_root) {
+        return Some(*boot_time);
+    }
+    let boot_time = read_boot_time(proc_root)?;
+    cache.insert(proc_root.to_path_buf(), boot_time);
+    Some(boot_time)
+}
+
+/// Executable, command line, working directory and full environment of
+/// `pid`, as `SpawnOptions` that relaunch it. Output is not captured.
+pub fn read_launch_options(proc_root: &Path, pid: u32) -> io::Result<SpawnOptions> {
+    let dir = proc_root.join(pid.to_string());
+    let mut cmd = parse_cmdline(&fs::read(dir.join("cmdline"))?).into_iter();
+    let arg0 = cmd
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "process has no command line"))?;
+    // argv[0] is whatever the process was started with (or rewrote it to), so
+    // the binary comes from the `exe` link instead.
+    let program = fs::read_link(dir.join("exe"))?
+        .to_string_lossy()
+        .to_string();
+    if program.ends_with(" (deleted)") {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "executable has been deleted or replaced",
+        ));
+    }
+    let cwd = fs::read_link(dir.join("cwd"))?;
+    let env = parse_cmdline(&fs::read(dir.join("environ"))?)
+        .into_iter()
+        .filter_map(|var| {
+            let (key, value) = var.split_once('=')?;
+            Some((key.to_string(), Some(value.to_string())))
+        })
+        .collect();
+
+    Ok(SpawnOptions {
+        program,
+        args: cmd.collect(),
+        arg0: Some(arg0),
+        cwd: Some(cwd.to_string_lossy().to_string()),
+        env,
+        clear_env: true,
+        ..Default::default()
+    })
+}
+
+/// `oom_score` and `oom_score_adj` of `pid`; each is None if unreadable.
+pub fn read_oom(proc_root: &Path, pid: u32) -> (Option<u32>, Option<i32>) {
+    let dir = proc_root.join(pid.to_string());
+    let read = |name: &str| fs::read_to_string(dir.join(name)).ok();
+    (
+        read("oom_score").and_then(|s| s.trim().parse().ok()),
+        read("oom_score_adj").and_then(|s| s.trim().parse().ok()),
+    )
+}
+
+/// Unified cgroup path of `pid`, or None if unreadable.
+pub fn read_cgroup(proc_root: &Path, pid: u32) -> Option<String> {
+    fs::read_to_string(proc_root.join(pid.to_string()).join("cgroup"))
+        .ok()
+        .and_then(|s| parse_cgroup(&s))
+}
+
+/// Run one reader, recording `fields` as restricted if the OS denied access.
+fn read_field<T>(
+    fields: &[DetailField],
+    restricted: &mut Vec<DetailField>,
+    read: impl FnOnce() -> io::Result<Option<T>>,
+) -> Option<T> {
+    match read() {
+        Ok(value) => value,
+        Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+            restricted.extend_from_slice(fields);
+            None
+        }
+        Err(_) => None,
+    }
+}
+
+fn sysconf(name: libc::c_int) -> Option<u64> {
+    let ret = unsafe { libc::sysconf(name) };
+    (ret > 0).then_some(ret as u64)
+}
+
+fn map_state(state: char) -> DtoStatus {
+    match state {
+        // W is "waking" on 2.6.33–3.13 kernels
+        'R' | 'W' => DtoStatus::Running,
+        'S' => DtoStatus::Sleeping,
+        'I' => DtoStatus::Idle,
+        'D' => DtoStatus::DiskSleep,
+        'T' => DtoStatus::Stopped,
+        't' => DtoStatus::TracingStop,
+        'Z' => DtoStatus::Zombie,
+        'X' | 'x' => DtoStatus::Dead,
+        'K' => DtoStatus::Wakekill,
+        'P' => DtoStatus::Parked,
+        _ => DtoStatus::Unknown,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Parsers
+// ---------------------------------------------------------------------------
+
+/// Fields we use from `/proc/<pid>/stat`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct StatFields {
+    comm: String,
+    state: char,
+    ppid: u32,
+    utime: u64,
+    stime: u64,
+    nice: i32,
+    num_threads: u32,
+    starttime: u64,
+    vsize: u64,
+    rt_priority: Option<u32>,
+    policy: Option<i32>,
+}
+
+/// Parse `/proc/<pid>/stat`. `comm` may itself contain spaces and parentheses,
+/// so everything after the *last* `)` is split positionally.
+fn parse_stat(content: &str) -> Option<StatFields> {
+    let open = content.find('(')?;
+    let close = content.rfind(')')?;
+    let comm = content.get(open + 1..close)?.to_string();
+    // Field 3 (state) is at index 0 here.
+    let rest: Vec<&str> = content.get(close + 1..)?.split_whitespace().collect();
+    let field = |n: usize| rest.get(n - 3).copied();
+
+    Some(StatFields {
+        comm,
+        state: field(3)?.chars().next()?,
+        ppid: field(4)?.parse().ok()?,
+        utime: field(14)?.parse().ok()?,
+        stime: field(15)?.parse().ok()?,
+        nice: field(19)?.parse().ok()?,
+        num_threads: field(20)?.parse().ok()?,
+        starttime: field(22)?.parse().ok()?,
+        vsize: field(23)?.parse().ok()?,
+        rt_priority: field(40).and_then(|v| v.parse().ok()),
+        policy: field(41).and_then(|v| v.parse().ok()),
+    })
+}
+
+/// Path from `/proc/<pid>/cgroup`: the unified (v2) entry, or the
+/// `name=systemd` one on cgroup v1 hosts.
+fn parse_cgroup(content: &str) -> Option<String> {
+    let mut v1 = None;
+    for line in content.lines() {
+        let mut parts = line.splitn(3, ':');
+        let (Some(id), Some(controllers), Some(path)) = (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        if id == "0" && controllers.is_empty() {
+            return Some(path.to_string());
+        }
+        if controllers == "name=systemd" {
+            v1 = Some(path.to_string());
+        }
+    }
+    v1
+}
+
+/// Real UID from the `Uid:` line of `/proc/<pid>/status`.
+fn parse_status_uid(content: &str) -> Option<u32> {
+    content
+        .lines()
+        .find_map(|l| l.strip_prefix("Uid:"))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|uid| uid.parse().ok())
+}
+
+/// First number after `key` in `/proc/<pid>/status` (e.g. `Threads:`, `VmSize:`).
+fn status_value<T: std::str::FromStr>(content: &str, key: &str) -> Option<T> {
+    content
+        .lines()
+        .find_map(|l| l.strip_prefix(key))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|v| v.parse().ok())
+}
+
+/// Resident pages (second column) of `/proc/<pid>/statm`.
+fn parse_statm_rss(content: &str) -> Option<u64> {
+    content.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// NUL-separated argv from `/proc/<pid>/cmdline`. Empty arguments are kept;
+/// only the terminating NUL is dropped.
+fn parse_cmdline(bytes: &[u8]) -> Vec<String> {
+    let bytes = bytes.strip_suffix(&[0]).unwrap_or(bytes);
+    if bytes.is_empty() {
+        return Vec::new();
+    }
+    bytes
+        .split(|b| *b == 0)
+        .map(|arg| String::from_utf8_lossy(arg).to_string())
+        .collect()
+}
+
+fn parse_io(content: &str) -> Option<ProcfsIo> {
+    let mut read_bytes = None;
+    let mut write_bytes = None;
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim().parse().ok();
+        match key {
+            "read_bytes" => read_bytes = value,
+            "write_bytes" => write_bytes = value,
+            _ => {}
+        }
+    }
+    Some(ProcfsIo {
+        read_bytes: read_bytes?,
+        write_bytes: write_bytes?,
+    })
+}
+
+/// Rows of `/proc/<pid>/limits`. Columns are space-padded and the names
+/// contain spaces, so each row is matched by its known name.
+fn parse_limits(content: &str) -> Vec<ResourceLimit> {
+    const NAMES: [(&str, RlimitResource); 16] = [
+        ("Max cpu time", RlimitResource::Cpu),
+        ("Max file size", RlimitResource::Fsize),
+        ("Max data size", RlimitResource::Data),
+        ("Max stack size", RlimitResource::Stack),
+        ("Max core file size", RlimitResource::Core),
+        ("Max resident set", RlimitResource::Rss),
+        ("Max processes", RlimitResource::Nproc),
+        ("Max open files", RlimitResource::Nofile),
+        ("Max locked memory", RlimitResource::Memlock),
+        ("Max address space", RlimitResource::As),
+        ("Max file locks", RlimitResource::Locks),
+        ("Max pending signals", RlimitResource::Sigpending),
+        ("Max msgqueue size", RlimitResource::Msgqueue),
+        ("Max nice priority", RlimitResource::Nice),
+        ("Max realtime priority", RlimitResource::Rtprio),
+        ("Max realtime timeout", RlimitResource::Rttime),
+    ];
+    let value = |v: &str| {
+        if v == "unlimited" {
+            Some(None)
+        } else {
+            v.parse().ok().map(Some)
+        }
+    };
+
+    content
+        .lines()
+        .filter_map(|line| {
+            let (name, resource) = NAMES.iter().find(|(name, _)| line.starts_with(name))?;
+            let mut cols = line[name.len()..].split_whitespace();
+            Some(ResourceLimit {
+                resource: *resource,
+                soft: value(cols.next()?)?,
+                hard: value(cols.next()?)?,
+                unit: cols.next().map(str::to_string),
+            })
+        })
+        .collect()
+}
+
+/// Sum of the aggregate `cpu` line and the number of `cpuN` lines in `/proc/stat`.
+fn parse_cpu_times(content: &str) -> Option<(u64, u64)> {
+    let mut total = None;
+    let mut cpus = 0;
+    for line in content.lines() {
+        if let Some(rest) = line.strip_prefix("cpu ") {
+            // user nice system idle iowait irq softirq steal; guest time is
+            // already counted in user/nice.
+            total = Some(
+                rest.split_whitespace()
+                    .take(8)
+                    .filter_map(|v| v.parse::<u64>().ok())
+                    .sum(),
+            );
+        } else if line.starts_with("cpu") {
+            cpus += 1;
+        }
+    }
+    Some((total?, cpus))
+}
+
+fn read_boot_time(proc_root: &Path) -> Option<u64> {
+    fs::read_to_string(proc_root.join("stat"))
+        .ok()?
+        .lines()
+        .find_map(|l| l.strip_prefix("btime "))
+        .and_then(|v| v.trim().parse().ok())
+}
+
+/// UID → username map from a passwd-format file.
+fn parse_passwd(content: &str) -> HashMap<u32, String> {
+    content
+        .lines()
+        .filter(|l| !l.starts_with('#'))
+        .filter_map(|l| {
+            let mut parts = l.split(':');
+            let name = parts.next()?;
+            let uid = parts.nth(1)?.parse().ok()?;
+            Some((uid, name.to_string()))
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{collector::fake_proc::FakeProc, models::ProcessKind};
+
+    #[test]
+    fn test_parse_stat_with_awkward_comm() {
+        let line = "42 (my) (proc) R 1 42 42 0 -1 0 0 0 0 0 7 3 0 0 20 0 5 0 1234 999 12 0";
+        let stat = parse_stat(line).unwrap();
+        assert_eq!(stat.comm, "my) (proc");
+        assert_eq!(stat.state, 'R');
+        assert_eq!(stat.ppid, 1);
+        assert_eq!(stat.utime, 7);
+        assert_eq!(stat.stime, 3);
+        assert_eq!(stat.num_threads, 5);
+        assert_eq!(stat.starttime, 1234);
+        assert_eq!(stat.vsize, 999);
+        assert_eq!(stat.nice, 0);
+        // Truncated line: the trailing scheduling fields are simply absent.
+        assert_eq!(stat.policy, None);
+    }
+
+    #[test]
+    fn test_parse_stat_priority_fields() {
+        let line = "7 (rt) S 1 7 7 0 -1 4194624 0 0 0 0 0 0 0 0 -51 -5 1 0 20 0 0 \
+                    18446744073709551615 0 0 0 0 0 0 0 0 0 0 0 0 17 2 50 1 0 0 0 0 0 0 0 0 0 0";
+        let stat = parse_stat(line).unwrap();
+        assert_eq!(stat.nice, -5);
+        assert_eq!(stat.rt_priority, Some(50));
+        assert_eq!(stat.policy, Some(libc::SCHED_FIFO));
+    }
+
+    #[test]
+    fn test_parse_limits() {
+        let content = "Limit                     Soft Limit           Hard Limit           Units     \n\
+                       Max cpu time              unlimited            unlimited            seconds   \n\
+                       Max open files            1024                 524288               files     \n\
+                       Max nice priority         0                    0                    \n";
+        let limits = parse_limits(content);
+        assert_eq!(limits.len(), 3);
+        assert_eq!(
+            limits[0],
+            ResourceLimit {
+                resource: RlimitResource::Cpu,
+                soft: None,
+                hard: None,
+                unit: Some("seconds".to_string()),
+            }
+        );
+        assert_eq!(limits[1].resource, RlimitResource::Nofile);
+        assert_eq!(limits[1].soft, Some(1024));
+        assert_eq!(limits[1].hard, Some(524288));
+        assert_eq!(limits[2].unit, None);
+    }
+
+    #[test]
+    fn test_read_launch_options() {
+        let fake = FakeProc::new();
+        fake.add(9, "server", 'S', 1, 1000, 0, 10, 1);
+        let dir = fake.proc_root().join("9");
+        fs::write(dir.join("cmdline"), "./bin/server\0--port\08080\0\0x\0").unwrap();
+        fs::write(dir.join("environ"), "PATH=/usr/bin\0MODE=a=b\0").unwrap();
+        std::os::unix::fs::symlink("/srv/app", dir.join("cwd")).unwrap();
+
+        let options = read_launch_options(&fake.proc_root(), 9).unwrap();
+        // The binary comes from the exe link; argv[0] is passed through as is.
+        assert_eq!(options.program, "/usr/bin/server");
+        assert_eq!(options.arg0.as_deref(), Some("./bin/server"));
+        assert_eq!(options.args, vec!["--port", "8080", "", "x"]);
+        assert_eq!(options.cwd.as_deref(), Some("/srv/app"));
+        assert!(options.clear_env);
+        assert_eq!(options.env.len(), 2);
+        assert_eq!(options.env["MODE"].as_deref(), Some("a=b"));
+
+        // `exec -a` style rewrites do not change what gets launched.
+        fs::write(dir.join("cmdline"), "python3\0-m\0http.server\0").unwrap();
+        let options = read_launch_options(&fake.proc_root(), 9).unwrap();
+        assert_eq!(options.program, "/usr/bin/server");
+        assert_eq!(options.arg0.as_deref(), Some("python3"));
+
+        fs::remove_file(dir.join("exe")).unwrap();
+        std::os::unix::fs::symlink("/usr/bin/server (deleted)", dir.join("exe")).unwrap();
+        assert!(read_launch_options(&fake.proc_root(), 9).is_err());
+
+        // Kernel threads have an empty command line and cannot be relaunched.
+        fs::write(dir.join("cmdline"), "").unwrap();
+        assert!(read_launch_options(&fake.proc_root(), 9).is_err());
+    }
+
+    #[test]
+    fn test_parse_io_requires_both_counters() {
+        assert_eq!(
+            parse_io("read_bytes: 1\nwrite_bytes: 2\n"),
+            Some(ProcfsIo {
+                read_bytes: 1,
+                write_bytes: 2
+            })
+        );
+        assert_eq!(parse_io("read_bytes: 1\n"), None);
+    }
+
+    #[test]
+    fn test_collect_maps_fixture_fields() {
+        let fake = FakeProc::new();
+        fake.add(1, "init", 'S', 0, 0, 0, 10, 100);
+        fake.add(1000, "bash", 'R', 1, 1000, 0, 500, 250);
+
+        let procs = fake.collector().collect();
+        assert_eq!(procs.len(), 2);
+
+        let bash = &procs[&1000];
+        assert_eq!(bash.name, "bash");
+        assert_eq!(bash.status, DtoStatus::Running);
+        assert_eq!(bash.user.as_deref(), Some("alice"));
+        assert_eq!(bash.parent_pid, Some(1));
+        assert_eq!(bash.path.as_deref(), Some("/usr/bin/bash"));
+        assert_eq!(bash.cmd, vec!["/usr/bin/bash", "--flag"]);
+        assert_eq!(bash.threads, Some(3));
+        assert_eq!(bash.disk_read_bytes, Some(4096));
+        assert_eq!(bash.nice, Some(0));
+        assert_eq!(bash.oom_score, Some(666));
+        assert_eq!(bash.oom_score_adj, Some(-100));
+        assert_eq!(
+            bash.memory_bytes,
+            250 * sysconf(libc::_SC_PAGESIZE).unwrap()
+        );
+        let ticks = sysconf(libc::_SC_CLK_TCK).unwrap();
+        assert_eq!(bash.start_time, Some(1_700_000_000 + 500 / ticks));
+
+        let init = &procs[&1];
+        assert_eq!(init.user.as_deref(), Some("root"));
+        assert_eq!(init.parent_pid, None);
+    }
+
+    #[test]
+    fn test_parse_cgroup() {
+        let v2 = "0::/system.slice/systemd-resolved.service\n";
+        assert_eq!(
+            parse_cgroup(v2).as_deref(),
+            Some("/system.slice/systemd-resolved.service")
+        );
+        let hybrid =
+            "12:cpu,cpuacct:/\n1:name=systemd:/user.slice/user-1000.slice\n0::/user.slice\n";
+        assert_eq!(parse_cgroup(hybrid).as_deref(), Some("/user.slice"));
+        let v1 = "4:memory:/\n1:name=systemd:/init.scope\n";
+        assert_eq!(parse_cgroup(v1).as_deref(), Some("/init.scope"));
+        assert_eq!(parse_cgroup("garbage\n"), None);
+    }
+
+    #[test]
+    fn test_collect_classifies_processes() {
+        let fake = FakeProc::new();
+        fake.add(2, "kthreadd", 'S', 0, 0, 0, 1, 0);
+        fake.add(80, "kworker/0:1", 'I', 2, 0, 0, 2, 0);
+        fake.add(700, "systemd-resolve", 'S', 1, 991, 0, 30, 10);
+        fake.add(1000, "bash", 'S', 1, 1000, 0, 500, 10);
+        fake.add(1001, "myapp", 'S', 1, 1000, 0, 500, 10);
+        for pid in [2, 80] {
+            fs::remove_file(fake.proc_root().join(pid.to_string()).join("exe")).unwrap();
+        }
+        fs::write(
+            fake.proc_root().join("1001").join("cgroup"),
+            "0::/system.slice/myapp.service\n",
+        )
+        .unwrap();
+
+        let procs = fake.collector().collect();
+        assert_eq!(procs[&2].kind, ProcessKind::Kernel);
+        assert_eq!(procs[&80].kind, ProcessKind::Kernel);
+        assert_eq!(procs[&80].status, DtoStatus::Idle);
+        assert_eq!(procs[&700].kind, ProcessKind::System);
+        assert_eq!(procs[&1000].kind, ProcessKind::User);
+        assert_eq!(procs[&1001].kind, ProcessKind::System);
+    }
+
+    #[test]
+    fn test_read_process_io_and_threads() {
+        let fake = FakeProc::new();
+        fake.add(7, "worker", 'S', 1, 1000, 0, 10, 1);
+        let p = fake.collector().read_process(7).unwrap();
+        assert_eq!(p.num_threads, 3);
+        assert_eq!(p.vsize_bytes, 8_192_000);
+        assert_eq!(
+            p.io,
+            Some(ProcfsIo {
+                read_bytes: 4096,
+                write_bytes: 8192
+            })
+        );
+
+        fs::remove_file(fake.proc_root().join("7/io")).unwrap();
+        assert_eq!(fake.collector().read_process(7).unwrap().io, None);
+    }
+
+    #[test]
+    fn test_read_details_from_fixture() {
+        let fake = FakeProc::new();
+        fake.add(9, "server", 'S', 1, 1000, 0, 10, 1);
+        let dir = fake.proc_root().join("9");
+        fs::write(
+            dir.join("status"),
+            "Name:\tserver\nUid:\t1000\t1000\t1000\t1000\nVmSize:\t    2048 kB\nThreads:\t4\n",
+        )
+        .unwrap();
+        fs::write(dir.join("environ"), "HOME=/home/alice\0LANG=C\0").unwrap();
+        fs::create_dir(dir.join("fd")).unwrap();
+        for fd in ["0", "1", "2"] {
+            fs::write(dir.join("fd").join(fd), "").unwrap();
+        }
+
+        let dto = fake.collector().collect().remove(&9).unwrap();
+        let details = read_details(&fake.proc_root(), dto);
+        assert_eq!(details.threads, Some(4));
+        assert_eq!(details.virtual_memory_bytes, Some(2048 * 1024));
+        assert_eq!(details.disk_read_bytes, Some(4096));
+        assert_eq!(details.disk_written_bytes, Some(8192));
+        assert_eq!(details.open_files_count, Some(3));
+        assert_eq!(
+            details.environment,
+            Some(vec!["HOME=/home/alice".to_string(), "LANG=C".to_string()])
+        );
+        assert!(details.restricted.is_empty());
+    }
+
+    #[test]
+    fn test_read_start_time_matches_collected_dto() {
+        let fake = FakeProc::new();
+        fake.add(5, "svc", 'S', 1, 0, 0, 4321, 1);
+        let dto = fake.collector().collect().remove(&5).unwrap();
+        assert_eq!(read_start_time(&fake.proc_root(), 5), dto.start_time);
+        assert_eq!(read_start_time(&fake.proc_root(), 6), None);
+
+        // A wall-clock step moves `btime`; identities taken earlier must still match.
+        fs::write(
+            fake.proc_root().join("stat"),
+            "cpu  0 0 0 0\nbtime 1700000090\n",
+        )
+        .unwrap();
+        assert_eq!(read_start_time(&fake.proc_root(), 5), dto.start_time);
+    }
+
+    #[test]
+    fn test_read_field_reports_permission_denied() {
+        let mut restricted = Vec::new();
+        let denied: Option<u32> = read_field(
+            &[DetailField::DiskReadBytes, DetailField::DiskWrittenBytes],
+            &mut restricted,
+            || Err(io::Error::from(io::ErrorKind::PermissionDenied)),
+        );
+        let missing: Option<u32> = read_field(&[DetailField::Environment], &mut restricted, || {
+            Err(io::Error::from(io::ErrorKind::NotFound))
+        });
+        assert_eq!(denied, None);
+        assert_eq!(missing, None);
+        assert_eq!(
+            restricted,
+            vec![DetailField::DiskReadBytes, DetailField::DiskWrittenBytes]
+        );
+    }
+
+    #[test]
+    fn test_cpu_percent_from_tick_deltas() {
+        let fake = FakeProc::new();
+        fake.add(200, "busy", 'R', 1, 1000, 100, 10, 1);
+        let mut collector = fake.collector();
+
+        // First sample has no baseline.
+        assert_eq!(collector.collect()[&200].cpu_percent, 0.0);
+
+        // 200 total ticks over two CPUs = 100 ticks per core; the process used 50.
+        fake.add_ticks(200, 150);
+        fake.set_cpu_total(1200);
+        let pct = collector.collect()[&200].cpu_percent;
+        assert!((pct - 50.0).abs() < 0.01, "got {pct}");
+    }
+
+    #[test]
+    fn test_recycled_pid_resets_cpu_baseline() {
+        let fake = FakeProc::new();
+        fake.add(300, "old", 'R', 1, 1000, 100, 10, 1);
+        let mut collector = fake.collector();
+        collector.collect();
+
+        fs::remove_dir_all(fake.proc_root().join("300")).unwrap();
+        fake.add(300, "new", 'R', 1, 1000, 180, 99, 1);
+        fake.set_cpu_total(1200);
+        let procs = collector.collect();
+        assert_eq!(procs[&300].name, "new");
+        assert_eq!(procs[&300].cpu_percent, 0.0);
+    }
+
+    #[test]
+    fn test_vanished_process_is_dropped() {
+        let fake = FakeProc::new();
+        fake.add(400, "short", 'S', 1, 1000, 0, 10, 1);
+        let mut collector = fake.collector();
+        assert!(collector.collect().contains_key(&400));
+
+        fs::remove_dir_all(fake.proc_root().join("400")).unwrap();
+        assert!(!collector.collect().contains_key(&400));
+    }
+}
@@ -1,15 +1,98 @@
-use std::collections::HashMap;
+#[cfg(target_os = "linux")]
+use std::path::{Path, PathBuf};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 use sysinfo::{
     Process, ProcessStatus, System, Users,
 };
 use tracing::trace;
 
-use crate::models::{ProcessDto, ProcessStatus as DtoStatus};
+use super::ProcessCollector;
+use crate::{
+    classify::{Classifier, ProcessTraits},
+    models::{ProcessDto, ProcessStatus as DtoStatus, SchedulingPolicy},
+};
+
+/// How long per-process extras read outside sysinfo are reused.
+const EXTRAS_TTL: Duration = Duration::from_secs(10);
 
 /// Wraps `sysinfo::System` and provides collected process snapshots.
 pub struct SysinfoCollector {
     system: System,
     users: Users,
+    classifier: Classifier,
+    extras: HashMap<u32, Extras>,
+    #[cfg(target_os = "linux")]
+    proc_root: PathBuf,
+}
+
+/// Fields sysinfo does not report. They are read when a PID is first seen
+/// and then at most every `EXTRAS_TTL`, not on every tick, so nice and OOM
+/// changes show up with that much delay.
+#[derive(Debug, Default)]
+struct Extras {
+    /// sysinfo's start time for the PID; a change means the PID was reused
+    key: u64,
+    read_at: Option<Instant>,
+    start_time: Option<u64>,
+    cgroup: Option<String>,
+    nice: Option<i32>,
+    scheduling_policy: Option<SchedulingPolicy>,
+    oom_score: Option<u32>,
+    oom_score_adj: Option<i32>,
+}
+
+impl Extras {
+    /// Read the extras of `pid`; `key` is sysinfo's start time for it.
+    #[cfg(target_os = "linux")]
+    fn read(proc_root: &Path, pid: u32, key: u64, now: Instant) -> Self {
+        use super::procfs_collector::{read_cgroup, read_oom, read_scheduling};
+
+        let (nice, scheduling_policy, start_time) = match read_scheduling(proc_root, pid) {
+            Some((nice, policy, start_time)) => (Some(nice), policy, Some(start_time)),
+            None => (None, None, None),
+        };
+        let (oom_score, oom_score_adj) = read_oom(proc_root, pid);
+        Self {
+            key,
+            read_at: Some(now),
+            start_time,
+            cgroup: read_cgroup(proc_root, pid),
+            nice,
+            scheduling_policy,
+            oom_score,
+            oom_score_adj,
+        }
+    }
+
+    #[cfg(all(unix, not(target_os = "linux")))]
+    fn read(pid: u32, key: u64, now: Instant) -> Self {
+        Self {
+            key,
+            read_at: Some(now),
+            nice: crate::priority::get_nice(pid),
+            scheduling_policy: crate::priority::scheduling_policy(pid),
+            ..Default::default()
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn read(_pid: u32, key: u64, now: Instant) -> Self {
+        Self {
+            key,
+            read_at: Some(now),
+            ..Default::default()
+        }
+    }
+
+    fn is_fresh(&self, key: u64, now: Instant) -> bool {
+        self.key == key
+            && self
+                .read_at
+                .is_some_and(|at| now.duration_since(at) < EXTRAS_TTL)
+    }
 }
 
 impl SysinfoCollector {
@@ -17,29 +100,43 @@ impl SysinfoCollector {
         let mut system = System::new_all();
         system.refresh_all();
         let users = Users::new_with_refreshed_list();
-        Self { system, users }
+        Self {
+            system,
+            users,
+            classifier: Classifier::new(),
+            extras: HashMap::new(),
+            #[cfg(target_os = "linux")]
+            proc_root: PathBuf::from(super::PROC_ROOT),
+        }
     }
 
-    /// Refresh process list and return a map of PID → DTO.
-    pub fn collect(&mut self) -> HashMap<u32, ProcessDto> {
-        self.system.refresh_all();
-        self.users.refresh();
-
-        let current_user = Self::get_current_username();
-
-        let mut result = HashMap::new();
+    /// Read nice, OOM, cgroup and start-time data from a different procfs
+    /// root (default `/proc`).
+    #[cfg(target_os = "linux")]
+    pub fn with_proc_root(mut self, proc_root: impl Into<PathBuf>) -> Self {
+        self.proc_root = proc_root.into();
+        self
+    }
 
-        for (pid, process) in self.system.processes() {
-            let pid_u32 = pid.as_u32();
-            let dto = self.map_process(pid_u32, process, &current_user);
-            result.insert(pid_u32, dto);
+    /// Make sure the cached extras of `pid` are current.
+    fn refresh_extras(&mut self, pid: u32, key: u64, now: Instant) {
+        if self.extras.get(&pid).is_some_and(|e| e.is_fresh(key, now)) {
+            return;
         }
-
-        trace!("Collected {} processes", result.len());
-        result
+        #[cfg(target_os = "linux")]
+        let extras = Extras::read(&self.proc_root, pid, key, now);
+        #[cfg(not(target_os = "linux"))]
+        let extras = Extras::read(pid, key, now);
+        self.extras.insert(pid, extras);
     }
 
-    fn map_process(&self, pid: u32, p: &Process, current_user: &str) -> ProcessDto {
+    fn map_process(
+        &self,
+        pid: u32,
+        p: &Process,
+        extras: &Extras,
+        current_user: &str,
+    ) -> ProcessDto {
         let status = map_status(p.status());
 
         let user_id = p.user_id();
@@ -52,6 +149,8 @@ impl SysinfoCollector {
 
         let path = p.exe().map(|e| e.to_string_lossy().to_string());
 
+        let cwd = p.cwd().map(|c| c.to_string_lossy().to_string());
+
         let parent_pid = p.parent().map(|pp| pp.as_u32());
 
         let needs_elevation = user_name
@@ -59,20 +158,50 @@ impl SysinfoCollector {
             .map(|u| u != current_user && !u.is_empty())
             .unwrap_or(false);
 
+        #[cfg(unix)]
+        let uid = user_id.map(|uid| **uid);
+        #[cfg(not(unix))]
+        let uid = None;
+        #[cfg(target_os = "linux")]
+        let kernel_thread = crate::classify::is_kernel_thread(pid, parent_pid, path.as_deref());
+        #[cfg(not(target_os = "linux"))]
+        let kernel_thread = false;
+        let kind = self.classifier.classify(&ProcessTraits {
+            uid,
+            user: user_name.as_deref(),
+            kernel_thread,
+            cgroup: extras.cgroup.as_deref(),
+        });
+
         let cmd: Vec<String> = p.cmd().iter().map(|s| s.to_string_lossy().to_string()).collect();
 
+        let disk = p.disk_usage();
+
         ProcessDto {
             pid,
             name: p.name().to_string_lossy().to_string(),
             status,
+            status_since_ms: None,
             cpu_percent: p.cpu_usage(),
             memory_bytes: p.memory(),
             user: user_name,
             path,
+            cwd,
             parent_pid,
-            start_time: Some(p.start_time()),
+            // On Linux from procfs, with the boot time `ProcessHandle` uses,
+            // so identity checks agree.
+            start_time: extras.start_time.or(Some(p.start_time())),
             needs_elevation,
+            kind,
             cmd,
+            threads: p.tasks().map(|t| t.len() as u32),
+            disk_read_bytes: Some(disk.total_read_bytes),
+            disk_written_bytes: Some(disk.total_written_bytes),
+            nice: extras.nice,
+            scheduling_policy: extras.scheduling_policy,
+            oom_score: extras.oom_score,
+            oom_score_adj: extras.oom_score_adj,
+            launched_by_app: false,
         }
     }
 
@@ -92,11 +221,51 @@ impl SysinfoCollector {
 
 fn map_status(s: ProcessStatus) -> DtoStatus {
     match s {
-        ProcessStatus::Run => DtoStatus::Running,
+        ProcessStatus::Run | ProcessStatus::Waking => DtoStatus::Running,
         ProcessStatus::Sleep => DtoStatus::Sleeping,
+        ProcessStatus::Idle => DtoStatus::Idle,
+        ProcessStatus::UninterruptibleDiskSleep => DtoStatus::DiskSleep,
         ProcessStatus::Stop => DtoStatus::Stopped,
+        ProcessStatus::Tracing => DtoStatus::TracingStop,
         ProcessStatus::Zombie => DtoStatus::Zombie,
-        _ => DtoStatus::Unknown,
+        ProcessStatus::Dead => DtoStatus::Dead,
+        ProcessStatus::Wakekill => DtoStatus::Wakekill,
+        ProcessStatus::Parked => DtoStatus::Parked,
+        ProcessStatus::LockBlocked => DtoStatus::LockBlocked,
+        ProcessStatus::Unknown(_) => DtoStatus::Unknown,
+    }
+}
+
+impl ProcessCollector for SysinfoCollector {
+    /// Refresh process list and return a map of PID → DTO.
+    fn collect(&mut self) -> HashMap<u32, ProcessDto> {
+        self.system.refresh_all();
+        self.users.refresh();
+
+        let current_user = Self::get_current_username();
+        let now = Instant::now();
+
+        let pids: Vec<(u32, u64)> = self
+            .system
+            .processes()
+            .iter()
+            .map(|(pid, p)| (pid.as_u32(), p.start_time()))
+            .collect();
+        for &(pid, key) in &pids {
+            self.refresh_extras(pid, key, now);
+        }
+
+        let mut result = HashMap::new();
+
+        for (pid, process) in self.system.processes() {
+            let pid_u32 = pid.as_u32();
+            let dto = self.map_process(pid_u32, process, &self.extras[&pid_u32], &current_user);
+            result.insert(pid_u32, dto);
+        }
+        self.extras.retain(|pid, _| result.contains_key(pid));
+
+        trace!("Collected {} processes", result.len());
+        result
     }
 }
 
@@ -114,23 +283,42 @@ impl Default for SysinfoCollector {
 mod tests {
     use super::*;
 
+    #[cfg(target_os = "linux")]
     #[test]
-    fn test_collector_returns_processes() {
-        let mut collector = SysinfoCollector::new();
-        let procs = collector.collect();
-        // Should always have at least the current process
-        assert!(!procs.is_empty(), "Expected at least one process");
-    }
+    fn test_extras_read_from_proc_root() {
+        use crate::collector::{fake_proc::FakeProc, procfs_collector::read_start_time};
+        use crate::models::ProcessKind;
 
-    #[test]
-    fn test_current_pid_present() {
-        let mut collector = SysinfoCollector::new();
-        let procs = collector.collect();
-        let current_pid = std::process::id();
-        assert!(
-            procs.contains_key(&current_pid),
-            "Current PID {} should be in the collected list",
-            current_pid
-        );
+        let fake = FakeProc::new();
+        fake.add(700, "myapp", 'S', 1, 1000, 0, 500, 10);
+        std::fs::write(
+            fake.proc_root().join("700").join("cgroup"),
+            "0::/system.slice/myapp.service\n",
+        )
+        .unwrap();
+
+        let now = Instant::now();
+        let extras = Extras::read(&fake.proc_root(), 700, 42, now);
+        assert_eq!(extras.nice, Some(0));
+        assert_eq!(extras.scheduling_policy, None);
+        assert_eq!(extras.oom_score, Some(666));
+        assert_eq!(extras.oom_score_adj, Some(-100));
+        assert_eq!(extras.start_time, read_start_time(&fake.proc_root(), 700));
+        // A login UID in a service slice is a system process.
+        let kind = Classifier::with_user_uids(1000..=60000).classify(&ProcessTraits {
+            uid: Some(1000),
+            cgroup: extras.cgroup.as_deref(),
+            ..Default::default()
+        });
+        assert_eq!(kind, ProcessKind::System);
+
+        assert!(extras.is_fresh(42, now));
+        // A reused PID or an old reading is read again.
+        assert!(!extras.is_fresh(43, now));
+        assert!(!extras.is_fresh(42, now + EXTRAS_TTL));
+
+        let missing = Extras::read(&fake.proc_root(), 701, 42, now);
+        assert_eq!(missing.start_time, None);
+        assert_eq!(missing.cgroup, None);
     }
 }
@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+#[cfg(target_os = "linux")]
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use sysinfo::System;
+
+use crate::models::{LoadAverage, ProcessDto, SystemPressure, SystemSnapshot};
+#[cfg(target_os = "linux")]
+use crate::models::{PressureAverages, PressureStall};
+
+/// Collects system-wide CPU, memory, load and pressure metrics.
+pub struct SystemCollector {
+    system: System,
+    #[cfg(target_os = "linux")]
+    proc_root: PathBuf,
+}
+
+impl SystemCollector {
+    pub fn new() -> Self {
+        let mut system = System::new();
+        system.refresh_cpu_usage();
+        system.refresh_memory();
+        Self {
+            system,
+            #[cfg(target_os = "linux")]
+            proc_root: PathBuf::from(super::PROC_ROOT),
+        }
+    }
+
+    /// Read page cache and PSI data from a different procfs root (default `/proc`).
+    #[cfg(target_os = "linux")]
+    pub fn with_proc_root(mut self, proc_root: impl Into<PathBuf>) -> Self {
+        self.proc_root = proc_root.into();
+        self
+    }
+
+    /// Refresh and build a snapshot; process and thread counts come from `processes`.
+    pub fn collect(
+        &mut self,
+        processes: &HashMap<u32, ProcessDto>,
+        timestamp_ms: u64,
+    ) -> SystemSnapshot {
+        self.system.refresh_cpu_usage();
+        self.system.refresh_memory();
+
+        let load = System::load_average();
+        let thread_counts: Vec<u64> = processes
+            .values()
+            .filter_map(|p| p.threads.map(u64::from))
+            .collect();
+
+        SystemSnapshot {
+            timestamp_ms,
+            cpu_percent: self.system.global_cpu_usage(),
+            per_core_cpu_percent: self.system.cpus().iter().map(|c| c.cpu_usage()).collect(),
+            memory_total_bytes: self.system.total_memory(),
+            memory_used_bytes: self.system.used_memory(),
+            memory_available_bytes: self.system.available_memory(),
+            memory_cached_bytes: self.read_cached_memory(),
+            swap_total_bytes: self.system.total_swap(),
+            swap_used_bytes: self.system.used_swap(),
+            load_average: LoadAverage {
+                one: load.one,
+                five: load.five,
+                fifteen: load.fifteen,
+            },
+            uptime_secs: System::uptime(),
+            process_count: processes.len(),
+            thread_count: (!thread_counts.is_empty()).then(|| thread_counts.iter().sum()),
+            pressure: self.read_pressure(),
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn read_cached_memory(&self) -> Option<u64> {
+        let meminfo = fs::read_to_string(self.proc_root.join("meminfo")).ok()?;
+        meminfo_kb(&meminfo, "Cached:").map(|kb| kb * 1024)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn read_cached_memory(&self) -> Option<u64> {
+        None
+    }
+
+    #[cfg(target_os = "linux")]
+    fn read_pressure(&self) -> Option<SystemPressure> {
+        let dir = self.proc_root.join("pressure");
+        let read = |name: &str| read_pressure_file(&dir.join(name));
+        let pressure = SystemPressure {
+            cpu: read("cpu"),
+            memory: read("memory"),
+            io: read("io"),
+        };
+        // Kernels without PSI (or with psi=0) have no files at all.
+        (pressure != SystemPressure::default()).then_some(pressure)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn read_pressure(&self) -> Option<SystemPressure> {
+        None
+    }
+}
+
+impl Default for SystemCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn meminfo_kb(content: &str, key: &str) -> Option<u64> {
+    content
+        .lines()
+        .find_map(|l| l.strip_prefix(key))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|v| v.parse().ok())
+}
+
+#[cfg(target_os = "linux")]
+fn read_pressure_file(path: &Path) -> Option<PressureStall> {
+    parse_pressure(&fs::read_to_string(path).ok()?)
+}
+
+/// Parse a PSI file:
+/// `some avg10=0.00 avg60=0.00 avg300=0.00 total=0` plus an optional `full ...` line.
+#[cfg(target_os = "linux")]
+fn parse_pressure(content: &str) -> Option<PressureStall> {
+    let mut some = None;
+    let mut full = None;
+    for line in content.lines() {
+        let Some((kind, rest)) = line.split_once(' ') else {
+            continue;
+        };
+        let averages = parse_pressure_averages(rest);
+        match kind {
+            "some" => some = averages,
+            "full" => full = averages,
+            _ => {}
+        }
+    }
+    Some(PressureStall { some: some?, full })
+}
+
+#[cfg(target_os = "linux")]
+fn parse_pressure_averages(fields: &str) -> Option<PressureAverages> {
+    let mut averages = PressureAverages::default();
+    for field in fields.split_whitespace() {
+        let (key, value) = field.split_once('=')?;
+        match key {
+            "avg10" => averages.avg10 = value.parse().ok()?,
+            "avg60" => averages.avg60 = value.parse().ok()?,
+            "avg300" => averages.avg300 = value.parse().ok()?,
+            "total" => averages.total_us = value.parse().ok()?,
+            _ => {}
+        }
+    }
+    Some(averages)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_system_snapshot_counts_processes() {
+        let mut collector = SystemCollector::new();
+        let snapshot = collector.collect(&HashMap::new(), 42);
+        assert_eq!(snapshot.timestamp_ms, 42);
+        assert_eq!(snapshot.process_count, 0);
+        assert_eq!(snapshot.thread_count, None);
+        assert!(snapshot.memory_total_bytes > 0);
+        assert!(!snapshot.per_core_cpu_percent.is_empty());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_pressure() {
+        let psi = "some avg10=1.50 avg60=0.25 avg300=0.00 total=12345\n\
+                   full avg10=0.10 avg60=0.00 avg300=0.00 total=678\n";
+        let stall = parse_pressure(psi).unwrap();
+        assert_eq!(stall.some.avg10, 1.5);
+        assert_eq!(stall.some.avg60, 0.25);
+        assert_eq!(stall.some.total_us, 12345);
+        assert_eq!(stall.full.unwrap().total_us, 678);
+
+        let cpu_only = parse_pressure("some avg10=0.00 avg60=0.00 avg300=0.00 total=1\n").unwrap();
+        assert_eq!(cpu_only.full, None);
+        assert_eq!(parse_pressure(""), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_pressure_and_cache_from_fixture_root() {
+        let root = std::env::temp_dir().join(format!("pm-fake-sys-{}", std::process::id()));
+        fs::create_dir_all(root.join("pressure")).unwrap();
+        fs::write(
+            root.join("meminfo"),
+            "MemTotal: 1000 kB\nCached:     256 kB\nSwapCached: 0 kB\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("pressure/memory"),
+            "some avg10=2.00 avg60=1.00 avg300=0.50 total=99\nfull avg10=0.00 avg60=0.00 avg300=0.00 total=0\n",
+        )
+        .unwrap();
+
+        let collector = SystemCollector::new().with_proc_root(&root);
+        let cached = collector.read_cached_memory();
+        let pressure = collector.read_pressure().unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(cached, Some(256 * 1024));
+        assert_eq!(pressure.memory.unwrap().some.avg10, 2.0);
+        assert_eq!(pressure.cpu, None);
+        assert_eq!(pressure.io, None);
+    }
+}
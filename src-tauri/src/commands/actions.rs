@@ -1,17 +1,46 @@
-use std::sync::Arc;
+#[cfg(unix)]
+use std::time::{Duration, Instant};
+use std::{
+    fs::OpenOptions,
+    io,
+    process::{Child, Command, Stdio},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use parking_lot::Mutex;
-use tauri::State;
-use tracing::info;
+#[cfg(unix)]
+use tauri::Emitter;
+use tauri::{AppHandle, State};
+use tracing::{debug, error, info};
 
+#[cfg(unix)]
+use crate::models::{signal_name, KillProgressEvent, KillStage};
+#[cfg(target_os = "linux")]
+use crate::{affinity, collector::procfs_collector::read_launch_options, limits};
 use crate::{
     error::AppError,
-    models::KillMode,
+    models::{
+        subtree_pids, AuditEntry, AuditOutcome, AuditQuery, IoPriorityClass, KillCandidate,
+        KillMatchingResult, KillMode, ProcessActionResult, ProcessDto, ProcessFilter,
+        ProtectionEntry, ProtectionRule, RlimitResource, SignalSpec, SpawnOptions, TreeKillOrder,
+    },
     state::AppState,
 };
+#[cfg(unix)]
+use crate::{priority, process_handle::ProcessHandle};
 
 type SharedState = Arc<Mutex<AppState>>;
 
+pub const EVENT_KILL_PROGRESS: &str = "kill:progress";
+
+/// How often a graceful terminate checks whether the process is gone.
+#[cfg(unix)]
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// How long to wait for the process to disappear after SIGKILL.
+#[cfg(unix)]
+const KILL_CONFIRM_TIMEOUT: Duration = Duration::from_secs(2);
+
 // ---------------------------------------------------------------------------
 // Kill / Terminate
 // ---------------------------------------------------------------------------
@@ -19,32 +48,878 @@ type SharedState = Arc<Mutex<AppState>>;
 /// Send a termination signal to a process.
 /// `mode = "terminate"` → SIGTERM / graceful
 /// `mode = "kill"`      → SIGKILL / forceful
+/// `mode = "graceful"`  → SIGTERM now, SIGKILL after the grace period (progress on `kill:progress`)
+///
+/// `start_time` is the start time the user saw; if the PID has since been
+/// reused the call fails with `IdentityMismatch` instead of signalling.
+/// Protected processes fail with `Protected` unless `force` is set.
 #[tauri::command]
 pub fn kill_process(
     pid: u32,
     mode: KillMode,
+    start_time: Option<u64>,
+    force: Option<bool>,
     state: State<'_, SharedState>,
+    app_handle: AppHandle,
 ) -> Result<(), AppError> {
-    // Validate PID is known
-    {
+    let detail = format!("mode={:?} force={}", mode, force.unwrap_or(false));
+    audited(&state, pid, "kill_process", detail, || {
+        let (expected, grace_ms) = {
+            let s = state.lock();
+            let expected = expected_start_time(&s, pid, start_time)?;
+            ensure_unprotected(&s, pid, force)?;
+            (expected, s.kill_grace_period_ms)
+        };
+
+        info!("kill_process: pid={} mode={:?}", pid, mode);
+
+        kill_target(&app_handle, &state, pid, expected, &mode, grace_ms)
+    })
+}
+
+/// Signal a process and all of its descendants, reporting the outcome per PID.
+/// `order` defaults to children-first; `start_time` identifies the root as in `kill_process`.
+///
+/// A protected root fails the whole call; protected descendants are skipped
+/// and reported as `Protected`. `force` overrides both.
+#[tauri::command]
+pub fn kill_tree(
+    pid: u32,
+    mode: KillMode,
+    order: Option<TreeKillOrder>,
+    start_time: Option<u64>,
+    force: Option<bool>,
+    state: State<'_, SharedState>,
+    app_handle: AppHandle,
+) -> Result<Vec<ProcessActionResult>, AppError> {
+    let order = order.unwrap_or_default();
+    let detail = format!(
+        "mode={:?} order={:?} root={} force={}",
+        mode,
+        order,
+        pid,
+        force.unwrap_or(false)
+    );
+
+    // Resolve the whole tree up front so processes re-parented mid-way are still covered.
+    let (targets, grace_ms) = {
         let s = state.lock();
-        if !s.snapshot.contains_key(&pid) {
-            return Err(AppError::NotFound { pid });
+        let refused = expected_start_time(&s, pid, start_time)
+            .and_then(|_| ensure_unprotected(&s, pid, force));
+        if let Err(e) = refused {
+            record_audit(
+                &s,
+                pid,
+                s.snapshot.get(&pid),
+                "kill_tree",
+                &detail,
+                Some(&e),
+            );
+            return Err(e);
         }
+        let targets: Vec<(ProcessDto, Result<(), AppError>)> =
+            subtree_pids(s.snapshot.values(), pid, order)
+                .into_iter()
+                .filter_map(|p| {
+                    let dto = s.snapshot.get(&p)?.clone();
+                    Some((dto, ensure_unprotected(&s, p, force)))
+                })
+                .collect();
+        (targets, s.kill_grace_period_ms)
+    };
+
+    info!(
+        "kill_tree: pid={} mode={:?} order={:?} targets={}",
+        pid,
+        mode,
+        order,
+        targets.len()
+    );
+
+    Ok(targets
+        .into_iter()
+        .map(|(p, allowed)| {
+            let error = allowed
+                .and_then(|()| {
+                    kill_target(&app_handle, &state, p.pid, p.start_time, &mode, grace_ms)
+                })
+                .err();
+            record_audit(
+                &state.lock(),
+                p.pid,
+                Some(&p),
+                "kill_tree",
+                &detail,
+                error.as_ref(),
+            );
+            ProcessActionResult {
+                pid: p.pid,
+                name: p.name,
+                error,
+            }
+        })
+        .collect())
+}
+
+/// Signal every process matching `filter`, each verified as in `kill_process`.
+/// With `dry_run` nothing is signalled and every matching process is returned,
+/// each with the reason it would be skipped if it is protected.
+///
+/// A filter without criteria is rejected instead of matching everything, and
+/// the process manager itself is never targeted. Unless `force` is set,
+/// protected processes are never signalled and are reported as `Protected`.
+#[tauri::command]
+pub fn kill_matching(
+    mut filter: ProcessFilter,
+    mode: KillMode,
+    dry_run: bool,
+    force: Option<bool>,
+    state: State<'_, SharedState>,
+    app_handle: AppHandle,
+) -> Result<KillMatchingResult, AppError> {
+    filter.compile()?;
+    let (candidates, grace_ms) = {
+        let s = state.lock();
+        (kill_candidates(&s, &filter, force)?, s.kill_grace_period_ms)
+    };
+
+    info!(
+        "kill_matching: filter={:?} mode={:?} dry_run={} targets={}",
+        filter,
+        mode,
+        dry_run,
+        candidates.len()
+    );
+
+    if dry_run {
+        return Ok(KillMatchingResult::DryRun { candidates });
+    }
+
+    let detail = format!("mode={:?} force={}", mode, force.unwrap_or(false));
+    let results = candidates
+        .into_iter()
+        .map(|candidate| {
+            let p = candidate.process;
+            let error = match candidate.protected {
+                Some(reason) => Some(AppError::Protected { pid: p.pid, reason }),
+                None => {
+                    kill_target(&app_handle, &state, p.pid, p.start_time, &mode, grace_ms).err()
+                }
+            };
+            record_audit(
+                &state.lock(),
+                p.pid,
+                Some(&p),
+                "kill_matching",
+                &detail,
+                error.as_ref(),
+            );
+            ProcessActionResult {
+                pid: p.pid,
+                name: p.name,
+                error,
+            }
+        })
+        .collect();
+    Ok(KillMatchingResult::Executed { results })
+}
+
+/// `matching_targets` paired with the protection that would skip each one.
+fn kill_candidates(
+    s: &AppState,
+    filter: &ProcessFilter,
+    force: Option<bool>,
+) -> Result<Vec<KillCandidate>, AppError> {
+    Ok(matching_targets(s, filter)?
+        .into_iter()
+        .map(|process| {
+            let protected = match ensure_unprotected(s, process.pid, force) {
+                Err(AppError::Protected { reason, .. }) => Some(reason),
+                _ => None,
+            };
+            KillCandidate { process, protected }
+        })
+        .collect())
+}
+
+/// Snapshot entries matched by `filter`, in PID order, excluding this process.
+fn matching_targets(s: &AppState, filter: &ProcessFilter) -> Result<Vec<ProcessDto>, AppError> {
+    if filter.is_empty() {
+        return Err(AppError::InvalidFilter {
+            message: "Filter has no criteria and would match every process".to_string(),
+        });
     }
 
-    info!("kill_process: pid={} mode={:?}", pid, mode);
+    let own_pid = std::process::id();
+    let mut targets: Vec<ProcessDto> = s
+        .snapshot
+        .values()
+        .filter(|p| p.pid != own_pid && filter.matches(p, &s.current_user))
+        .cloned()
+        .collect();
+    targets.sort_by_key(|p| p.pid);
+    Ok(targets)
+}
+
+// ---------------------------------------------------------------------------
+// Arbitrary signals / suspend / resume
+// ---------------------------------------------------------------------------
+
+/// Send any POSIX signal, given by name (`"SIGHUP"`, `"usr1"`) or number.
+#[tauri::command]
+pub fn send_signal(
+    pid: u32,
+    signal: SignalSpec,
+    start_time: Option<u64>,
+    force: Option<bool>,
+    state: State<'_, SharedState>,
+) -> Result<(), AppError> {
+    let detail = format!("signal={} force={}", signal, force.unwrap_or(false));
+    audited(&state, pid, "send_signal", detail, || {
+        let expected = {
+            let s = state.lock();
+            let expected = expected_start_time(&s, pid, start_time)?;
+            ensure_unprotected(&s, pid, force)?;
+            expected
+        };
+
+        #[cfg(unix)]
+        {
+            let sig = signal.resolve()?;
+            info!(
+                "send_signal: pid={} signal={}",
+                pid,
+                signal_name(sig).unwrap_or_else(|| sig.to_string())
+            );
+            ProcessHandle::open(pid, expected)?.signal(sig)
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = (signal, expected);
+            Err(AppError::Unsupported {
+                feature: "send_signal".to_string(),
+            })
+        }
+    })
+}
 
+/// Freeze a process (SIGSTOP).
+#[tauri::command]
+pub fn suspend_process(
+    pid: u32,
+    start_time: Option<u64>,
+    force: Option<bool>,
+    state: State<'_, SharedState>,
+) -> Result<(), AppError> {
+    let detail = format!("force={}", force.unwrap_or(false));
+    audited(&state, pid, "suspend_process", detail, || {
+        let expected = {
+            let s = state.lock();
+            let expected = expected_start_time(&s, pid, start_time)?;
+            ensure_unprotected(&s, pid, force)?;
+            expected
+        };
+        info!("suspend_process: pid={}", pid);
+
+        #[cfg(unix)]
+        {
+            ProcessHandle::open(pid, expected)?.signal(libc::SIGSTOP)
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = expected;
+            Err(AppError::Unsupported {
+                feature: "suspend_process".to_string(),
+            })
+        }
+    })
+}
+
+/// Resume a stopped process (SIGCONT).
+#[tauri::command]
+pub fn resume_process(
+    pid: u32,
+    start_time: Option<u64>,
+    state: State<'_, SharedState>,
+) -> Result<(), AppError> {
+    audited(&state, pid, "resume_process", String::new(), || {
+        let expected = expected_start_time(&state.lock(), pid, start_time)?;
+        info!("resume_process: pid={}", pid);
+
+        #[cfg(unix)]
+        {
+            ProcessHandle::open(pid, expected)?.signal(libc::SIGCONT)
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = expected;
+            Err(AppError::Unsupported {
+                feature: "resume_process".to_string(),
+            })
+        }
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Priority / affinity / limits
+// ---------------------------------------------------------------------------
+
+/// Renice a process (`nice` from -20, highest priority, to 19, lowest).
+/// Raising priority usually needs elevated privileges.
+#[tauri::command]
+pub fn set_priority(
+    pid: u32,
+    nice: i32,
+    start_time: Option<u64>,
+    state: State<'_, SharedState>,
+) -> Result<(), AppError> {
+    let detail = format!("nice={}", nice);
+    audited(&state, pid, "set_priority", detail, || {
+        let expected = expected_start_time(&state.lock(), pid, start_time)?;
+        info!("set_priority: pid={} nice={}", pid, nice);
+
+        #[cfg(unix)]
+        {
+            // setpriority(2) has no pidfd variant; re-verifying right before the
+            // call leaves only a very small reuse window.
+            let handle = ProcessHandle::open(pid, expected)?;
+            priority::set_nice(handle.pid(), nice)
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = (nice, expected);
+            Err(AppError::Unsupported {
+                feature: "set_priority".to_string(),
+            })
+        }
+    })
+}
+
+/// Restrict a process to the given CPUs (indices as in `/proc/cpuinfo`).
+/// Applies to every thread unless `all_threads` is false.
+#[tauri::command]
+pub fn set_affinity(
+    pid: u32,
+    cpus: Vec<usize>,
+    all_threads: Option<bool>,
+    start_time: Option<u64>,
+    state: State<'_, SharedState>,
+) -> Result<(), AppError> {
+    let detail = format!(
+        "cpus={:?} all_threads={}",
+        cpus,
+        all_threads.unwrap_or(true)
+    );
+    audited(&state, pid, "set_affinity", detail, || {
+        let expected = expected_start_time(&state.lock(), pid, start_time)?;
+        info!("set_affinity: pid={} cpus={:?}", pid, cpus);
+
+        #[cfg(target_os = "linux")]
+        {
+            let handle = ProcessHandle::open(pid, expected)?;
+            affinity::set_process_affinity(handle.pid(), &cpus, all_threads.unwrap_or(true))
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = (cpus, all_threads, expected);
+            Err(AppError::Unsupported {
+                feature: "set_affinity".to_string(),
+            })
+        }
+    })
+}
+
+/// Change a process's I/O scheduling class and level (0 highest … 7 lowest).
+/// `idle` only gets disk time when nothing else wants it.
+#[tauri::command]
+pub fn set_io_priority(
+    pid: u32,
+    class: IoPriorityClass,
+    level: u8,
+    start_time: Option<u64>,
+    state: State<'_, SharedState>,
+) -> Result<(), AppError> {
+    let detail = format!("class={:?} level={}", class, level);
+    audited(&state, pid, "set_io_priority", detail, || {
+        let expected = expected_start_time(&state.lock(), pid, start_time)?;
+        info!(
+            "set_io_priority: pid={} class={:?} level={}",
+            pid, class, level
+        );
+
+        #[cfg(target_os = "linux")]
+        {
+            let handle = ProcessHandle::open(pid, expected)?;
+            priority::set_io_priority(handle.pid(), class, level)
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = expected;
+            Err(AppError::Unsupported {
+                feature: "set_io_priority".to_string(),
+            })
+        }
+    })
+}
+
+/// Change one resource limit of a live process (`None` = unlimited).
+#[tauri::command]
+pub fn set_rlimit(
+    pid: u32,
+    resource: RlimitResource,
+    soft: Option<u64>,
+    hard: Option<u64>,
+    start_time: Option<u64>,
+    state: State<'_, SharedState>,
+) -> Result<(), AppError> {
+    let detail = format!("resource={:?} soft={:?} hard={:?}", resource, soft, hard);
+    audited(&state, pid, "set_rlimit", detail, || {
+        let expected = expected_start_time(&state.lock(), pid, start_time)?;
+        info!(
+            "set_rlimit: pid={} resource={:?} soft={:?} hard={:?}",
+            pid, resource, soft, hard
+        );
+
+        #[cfg(target_os = "linux")]
+        {
+            let handle = ProcessHandle::open(pid, expected)?;
+            limits::set_rlimit(handle.pid(), resource, soft, hard)
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = (soft, hard, expected);
+            Err(AppError::Unsupported {
+                feature: "set_rlimit".to_string(),
+            })
+        }
+    })
+}
+
+/// Set how likely the OOM killer is to pick a process
+/// (-1000 = never … 1000 = first).
+#[tauri::command]
+pub fn set_oom_score_adj(
+    pid: u32,
+    value: i32,
+    start_time: Option<u64>,
+    state: State<'_, SharedState>,
+) -> Result<(), AppError> {
+    let detail = format!("value={}", value);
+    audited(&state, pid, "set_oom_score_adj", detail, || {
+        let s = state.lock();
+        let expected = expected_start_time(&s, pid, start_time)?;
+        info!("set_oom_score_adj: pid={} value={}", pid, value);
+
+        #[cfg(target_os = "linux")]
+        {
+            let proc_root = s.proc_root.clone();
+            drop(s);
+            let handle = ProcessHandle::open(pid, expected)?;
+            limits::set_oom_score_adj(&proc_root, handle.pid(), value)
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = (value, expected);
+            Err(AppError::Unsupported {
+                feature: "set_oom_score_adj".to_string(),
+            })
+        }
+    })
+}
+
+/// Set how long graceful terminates wait before escalating (100 – 60_000 ms).
+#[tauri::command]
+pub fn set_kill_grace_period(ms: u64, state: State<'_, SharedState>) -> Result<(), AppError> {
+    let ms = ms.clamp(100, 60_000);
+    state.lock().kill_grace_period_ms = ms;
+    debug!("Kill grace period set to {}ms", ms);
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Protection list
+// ---------------------------------------------------------------------------
+
+/// Built-in and user-defined protection rules.
+#[tauri::command]
+pub fn list_protection_rules(state: State<'_, SharedState>) -> Vec<ProtectionEntry> {
+    state.lock().protection.entries()
+}
+
+/// Replace the user-defined protection rules; built-in ones always apply.
+#[tauri::command]
+pub fn set_protection_rules(
+    rules: Vec<ProtectionRule>,
+    state: State<'_, SharedState>,
+) -> Result<(), AppError> {
+    info!("set_protection_rules: {} rules", rules.len());
+    state
+        .lock()
+        .protection
+        .set_custom(rules)
+        .map_err(|e| AppError::OsError {
+            message: format!("Cannot save protection rules: {}", e),
+        })
+}
+
+// ---------------------------------------------------------------------------
+// Launch
+// ---------------------------------------------------------------------------
+
+/// Start a new process and return its PID.
+///
+/// The child is tagged `launched_by_app` in later snapshots and reaped in the
+/// background when it exits, so it never lingers as a zombie.
+#[tauri::command]
+pub fn spawn_process(
+    options: SpawnOptions,
+    state: State<'_, SharedState>,
+) -> Result<u32, AppError> {
+    info!(
+        "spawn_process: program={} args={:?} cwd={:?}",
+        options.program, options.args, options.cwd
+    );
+
+    launch(&options, state.inner())
+}
+
+/// Terminate a process and start it again with the same command line, working
+/// directory and environment. Returns the new PID.
+///
+/// Uses the graceful kill (SIGTERM, then SIGKILL after the grace period) and
+/// reports its stages on `kill:progress`. Output of the new process is discarded.
+#[tauri::command]
+pub async fn restart_process(
+    pid: u32,
+    start_time: Option<u64>,
+    force: Option<bool>,
+    state: State<'_, SharedState>,
+    app_handle: AppHandle,
+) -> Result<u32, AppError> {
+    let target = state.lock().snapshot.get(&pid).cloned();
+    let detail = format!("force={}", force.unwrap_or(false));
+    let result = async {
+        let (expected, grace_ms) = {
+            let s = state.lock();
+            let expected = expected_start_time(&s, pid, start_time)?;
+            ensure_unprotected(&s, pid, force)?;
+            (expected, s.kill_grace_period_ms)
+        };
+        info!("restart_process: pid={}", pid);
+
+        #[cfg(target_os = "linux")]
+        {
+            let proc_root = state.lock().proc_root.clone();
+            let handle = ProcessHandle::open(pid, expected)?;
+            // Capture everything before the process is gone.
+            let options = read_launch_options(&proc_root, pid).map_err(|e| match e.kind() {
+                io::ErrorKind::PermissionDenied => AppError::PermissionDenied {
+                    pid,
+                    message: "Cannot read this process's command line or environment".to_string(),
+                },
+                io::ErrorKind::NotFound => AppError::NotFound { pid },
+                _ => AppError::OsError {
+                    message: format!("Cannot relaunch process {pid}: {e}"),
+                },
+            })?;
+
+            handle.signal(libc::SIGTERM)?;
+            let report = |event: KillProgressEvent| {
+                if let Err(e) = app_handle.emit(EVENT_KILL_PROGRESS, &event) {
+                    error!("Failed to emit kill:progress: {}", e);
+                }
+            };
+            let stage = escalate_after_grace(
+                state.inner(),
+                &handle,
+                Duration::from_millis(grace_ms),
+                report,
+            )
+            .await;
+            if !matches!(
+                stage,
+                KillStage::ExitedAfterTerminate | KillStage::ExitedAfterKill
+            ) {
+                return Err(AppError::OsError {
+                    message: format!("Process {pid} did not exit ({stage:?}); not relaunching"),
+                });
+            }
+
+            launch(&options, state.inner())
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = (expected, grace_ms, app_handle);
+            Err(AppError::Unsupported {
+                feature: "restart_process".to_string(),
+            })
+        }
+    }
+    .await;
+
+    record_audit(
+        &state.lock(),
+        pid,
+        target.as_ref(),
+        "restart_process",
+        &detail,
+        result.as_ref().err(),
+    );
+    result
+}
+
+/// Start `options`, register the child as launched by us and reap it in the background.
+fn launch(options: &SpawnOptions, state: &SharedState) -> Result<u32, AppError> {
+    let mut child = spawn_child(options)?;
+    let pid = child.id();
+    let mut s = state.lock();
+    let start_time = launched_start_time(&s, pid);
+    s.launched.insert(pid, start_time);
+    drop(s);
+
+    let shared = state.clone();
+    std::thread::spawn(move || {
+        match child.wait() {
+            Ok(status) => info!("Launched process {} exited: {}", pid, status),
+            Err(e) => error!("Failed to wait for launched process {}: {}", pid, e),
+        }
+        shared.lock().launched.remove(&pid);
+    });
+
+    Ok(pid)
+}
+
+/// Build and start the command described by `options`.
+fn spawn_child(options: &SpawnOptions) -> Result<Child, AppError> {
+    let failed = |message: String| AppError::SpawnFailed {
+        program: options.program.clone(),
+        message,
+    };
+    if options.program.trim().is_empty() {
+        return Err(failed("No program given".to_string()));
+    }
+
+    let mut cmd = Command::new(&options.program);
+    cmd.args(&options.args).stdin(Stdio::null());
+    if options.clear_env {
+        cmd.env_clear();
+    }
+    if let Some(cwd) = &options.cwd {
+        cmd.current_dir(cwd);
+    }
+    for (key, value) in &options.env {
+        match value {
+            Some(value) => cmd.env(key, value),
+            None => cmd.env_remove(key),
+        };
+    }
+    cmd.stdout(capture_to(options.stdout_path.as_deref()).map_err(|e| failed(e.to_string()))?);
+    cmd.stderr(capture_to(options.stderr_path.as_deref()).map_err(|e| failed(e.to_string()))?);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+
+        if let Some(arg0) = &options.arg0 {
+            cmd.arg0(arg0);
+        }
+        // Own process group, so Ctrl+C aimed at the manager does not reach it.
+        cmd.process_group(0);
+        if let Some(nice) = options.nice {
+            if !(priority::NICE_MIN..=priority::NICE_MAX).contains(&nice) {
+                return Err(AppError::InvalidPriority { value: nice });
+            }
+            // Applied in the child before exec, so it never runs at the default priority.
+            unsafe {
+                cmd.pre_exec(move || {
+                    if libc::setpriority(libc::PRIO_PROCESS, 0, nice) != 0 {
+                        return Err(io::Error::last_os_error());
+                    }
+                    Ok(())
+                });
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    if options.nice.is_some() {
+        return Err(AppError::Unsupported {
+            feature: "spawn_process nice".to_string(),
+        });
+    }
+
+    cmd.spawn().map_err(|e| failed(e.to_string()))
+}
+
+/// Stdio that appends to `path`, or discards output when there is none.
+fn capture_to(path: Option<&str>) -> io::Result<Stdio> {
+    match path {
+        Some(path) => OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map(Stdio::from),
+        None => Ok(Stdio::null()),
+    }
+}
+
+/// Start time of a just-launched child, matched against later snapshots.
+#[cfg(target_os = "linux")]
+fn launched_start_time(s: &AppState, pid: u32) -> Option<u64> {
+    crate::collector::procfs_collector::read_start_time(&s.proc_root, pid)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn launched_start_time(_s: &AppState, _pid: u32) -> Option<u64> {
+    None
+}
+
+// ---------------------------------------------------------------------------
+// Process identity
+// ---------------------------------------------------------------------------
+
+/// The start time `pid` must still have when it is signalled.
+///
+/// Fails with `NotFound` unless `pid` is in the latest snapshot, and with
+/// `IdentityMismatch` if the caller's `start_time` disagrees with it. Without
+/// one from the caller the snapshot's own start time is used.
+fn expected_start_time(
+    s: &AppState,
+    pid: u32,
+    start_time: Option<u64>,
+) -> Result<Option<u64>, AppError> {
+    let dto = s.snapshot.get(&pid).ok_or(AppError::NotFound { pid })?;
+    match (start_time, dto.start_time) {
+        (Some(expected), Some(actual)) if expected != actual => Err(AppError::IdentityMismatch {
+            pid,
+            expected_start_time: expected,
+            actual_start_time: actual,
+        }),
+        (Some(expected), _) => Ok(Some(expected)),
+        (None, known) => Ok(known),
+    }
+}
+
+/// Fail with `Protected` if `pid` matches the protection list, unless `force` is set.
+fn ensure_unprotected(s: &AppState, pid: u32, force: Option<bool>) -> Result<(), AppError> {
+    if force == Some(true) {
+        return Ok(());
+    }
+    match s.snapshot.get(&pid).and_then(|p| s.protection.reason(p)) {
+        Some(reason) => Err(AppError::Protected {
+            pid,
+            reason: reason.to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Audit log
+// ---------------------------------------------------------------------------
+
+/// Audit log entries matching `query`, newest first.
+#[tauri::command]
+pub fn list_audit_log(
+    query: Option<AuditQuery>,
+    state: State<'_, SharedState>,
+) -> Result<Vec<AuditEntry>, AppError> {
+    // Read outside the lock; the log is only a path.
+    let Some(log) = state.lock().audit.clone() else {
+        return Ok(Vec::new());
+    };
+    log.read(&query.unwrap_or_default())
+        .map_err(|e| AppError::OsError {
+            message: format!("Cannot read audit log {}: {}", log.path().display(), e),
+        })
+}
+
+/// Run an action against `pid` and record its outcome in the audit log.
+fn audited<T>(
+    state: &SharedState,
+    pid: u32,
+    action: &str,
+    detail: String,
+    f: impl FnOnce() -> Result<T, AppError>,
+) -> Result<T, AppError> {
+    // Captured up front: after a kill the next refresh drops the process.
+    let target = state.lock().snapshot.get(&pid).cloned();
+    let result = f();
+    record_audit(
+        &state.lock(),
+        pid,
+        target.as_ref(),
+        action,
+        &detail,
+        result.as_ref().err(),
+    );
+    result
+}
+
+/// Append one entry to the audit log. Write failures are logged, not returned:
+/// the action itself has already happened.
+fn record_audit(
+    s: &AppState,
+    pid: u32,
+    target: Option<&ProcessDto>,
+    action: &str,
+    detail: &str,
+    error: Option<&AppError>,
+) {
+    let Some(log) = &s.audit else {
+        return;
+    };
+    let entry = AuditEntry {
+        timestamp_ms: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0),
+        actor: s.current_user.clone(),
+        pid,
+        start_time: target.and_then(|p| p.start_time),
+        name: target.map(|p| p.name.clone()),
+        cmd: target.map(|p| p.cmd.clone()).unwrap_or_default(),
+        action: action.to_string(),
+        detail: detail.to_string(),
+        outcome: match error {
+            None => AuditOutcome::Success,
+            Some(e) => AuditOutcome::Failed { error: e.clone() },
+        },
+    };
+    if let Err(e) = log.append(&entry) {
+        error!("Failed to write audit log {}: {}", log.path().display(), e);
+    }
+}
+
+/// Verify the target, deliver the kill and, for `Graceful`, start escalation.
+fn kill_target(
+    app_handle: &AppHandle,
+    state: &SharedState,
+    pid: u32,
+    expected: Option<u64>,
+    mode: &KillMode,
+    grace_ms: u64,
+) -> Result<(), AppError> {
     #[cfg(unix)]
     {
-        kill_unix(pid, &mode)
+        let handle = ProcessHandle::open(pid, expected)?;
+        let sig = match mode {
+            KillMode::Terminate | KillMode::Graceful => libc::SIGTERM,
+            KillMode::Kill => libc::SIGKILL,
+        };
+        handle.signal(sig)?;
+        if *mode == KillMode::Graceful {
+            spawn_escalation(app_handle.clone(), Arc::clone(state), handle, grace_ms);
+        }
+        Ok(())
     }
     #[cfg(windows)]
     {
-        kill_windows(pid, &mode)
+        let _ = (app_handle, state, expected, grace_ms);
+        kill_windows(pid, mode)
     }
     #[cfg(not(any(unix, windows)))]
     {
+        let _ = (app_handle, state, pid, expected, mode, grace_ms);
         Err(AppError::Unsupported {
             feature: "kill_process".to_string(),
         })
@@ -52,37 +927,106 @@ pub fn kill_process(
 }
 
 // ---------------------------------------------------------------------------
-// Unix implementation
+// Graceful terminate with escalation (Unix)
 // ---------------------------------------------------------------------------
 
+/// Watch a process that was just sent SIGTERM and escalate in the background,
+/// emitting each stage as a `kill:progress` event.
 #[cfg(unix)]
-fn kill_unix(pid: u32, mode: &KillMode) -> Result<(), AppError> {
-    use std::io;
+fn spawn_escalation(
+    app_handle: AppHandle,
+    state: SharedState,
+    handle: ProcessHandle,
+    grace_ms: u64,
+) {
+    tauri::async_runtime::spawn(async move {
+        let report = |event: KillProgressEvent| {
+            if let Err(e) = app_handle.emit(EVENT_KILL_PROGRESS, &event) {
+                error!("Failed to emit kill:progress: {}", e);
+            }
+        };
+        let stage =
+            escalate_after_grace(&state, &handle, Duration::from_millis(grace_ms), report).await;
+        info!(
+            "graceful kill: pid={} finished with {:?}",
+            handle.pid(),
+            stage
+        );
+    });
+}
 
-    let sig = match mode {
-        KillMode::Terminate => libc::SIGTERM,
-        KillMode::Kill => libc::SIGKILL,
+/// Wait up to `grace` for the process to exit after SIGTERM, then send SIGKILL.
+/// Every stage is passed to `report`; the final one is also returned. The
+/// SIGKILL, or the failure to send it, gets its own audit entry.
+#[cfg(unix)]
+async fn escalate_after_grace(
+    state: &SharedState,
+    handle: &ProcessHandle,
+    grace: Duration,
+    report: impl Fn(KillProgressEvent),
+) -> KillStage {
+    let started = Instant::now();
+    let event = |stage, error| KillProgressEvent {
+        pid: handle.pid(),
+        stage,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+        grace_period_ms: grace.as_millis() as u64,
+        error,
     };
 
-    let ret = unsafe { libc::kill(pid as libc::pid_t, sig) };
+    report(event(KillStage::TerminateSent, None));
 
-    if ret == 0 {
-        Ok(())
+    if wait_for_exit(handle, grace).await {
+        report(event(KillStage::ExitedAfterTerminate, None));
+        return KillStage::ExitedAfterTerminate;
+    }
+
+    let result = handle.signal(libc::SIGKILL);
+    if !matches!(result, Err(AppError::NotFound { .. })) {
+        let s = state.lock();
+        record_audit(
+            &s,
+            handle.pid(),
+            s.snapshot.get(&handle.pid()),
+            "kill_escalation",
+            &format!("signal=SIGKILL grace_ms={}", grace.as_millis()),
+            result.as_ref().err(),
+        );
+    }
+    match result {
+        Ok(()) => report(event(KillStage::KillSent, None)),
+        // Exited between the last poll and the SIGKILL.
+        Err(AppError::NotFound { .. }) => {
+            report(event(KillStage::ExitedAfterTerminate, None));
+            return KillStage::ExitedAfterTerminate;
+        }
+        Err(e) => {
+            report(event(KillStage::Failed, Some(e)));
+            return KillStage::Failed;
+        }
+    }
+
+    let stage = if wait_for_exit(handle, KILL_CONFIRM_TIMEOUT).await {
+        KillStage::ExitedAfterKill
     } else {
-        let err = io::Error::last_os_error();
-        let raw = err.raw_os_error().unwrap_or(0);
-        if raw == libc::EPERM {
-            Err(AppError::PermissionDenied {
-                pid,
-                message: "Insufficient permissions to signal this process".to_string(),
-            })
-        } else if raw == libc::ESRCH {
-            Err(AppError::NotFound { pid })
-        } else {
-            Err(AppError::OsError {
-                message: err.to_string(),
-            })
+        KillStage::StillRunning
+    };
+    report(event(stage, None));
+    stage
+}
+
+/// Poll until the process is gone or `timeout` elapses. Returns true if it exited.
+#[cfg(unix)]
+async fn wait_for_exit(handle: &ProcessHandle, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if !handle.is_running() {
+            return true;
         }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        tokio::time::sleep(EXIT_POLL_INTERVAL).await;
     }
 }
 
@@ -172,3 +1116,296 @@ pub fn copy_to_clipboard(text: String, app_handle: tauri::AppHandle) -> Result<(
 
 #[cfg(unix)]
 extern crate libc;
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ProcessDto, ProcessStatus};
+
+    fn state_with(pid: u32, start_time: Option<u64>) -> AppState {
+        let mut state = AppState::new("alice".to_string());
+        state.snapshot.insert(
+            pid,
+            ProcessDto {
+                start_time,
+                ..ProcessDto::test(pid, "proc")
+            },
+        );
+        state
+    }
+
+    #[test]
+    fn test_expected_start_time() {
+        let state = state_with(42, Some(1000));
+        assert_eq!(expected_start_time(&state, 42, None).unwrap(), Some(1000));
+        assert_eq!(
+            expected_start_time(&state, 42, Some(1000)).unwrap(),
+            Some(1000)
+        );
+        assert!(matches!(
+            expected_start_time(&state, 42, Some(999)),
+            Err(AppError::IdentityMismatch {
+                pid: 42,
+                expected_start_time: 999,
+                actual_start_time: 1000
+            })
+        ));
+        assert!(matches!(
+            expected_start_time(&state, 7, None),
+            Err(AppError::NotFound { pid: 7 })
+        ));
+
+        // Without a known start time the caller's value is still enforced later.
+        let state = state_with(42, None);
+        assert_eq!(expected_start_time(&state, 42, Some(5)).unwrap(), Some(5));
+    }
+
+    #[test]
+    fn test_ensure_unprotected() {
+        let mut state = state_with(42, None);
+        assert!(ensure_unprotected(&state, 42, None).is_ok());
+
+        state
+            .protection
+            .set_custom(vec![ProtectionRule::Name("proc".to_string())])
+            .unwrap();
+        assert!(matches!(
+            ensure_unprotected(&state, 42, None),
+            Err(AppError::Protected { pid: 42, .. })
+        ));
+        assert!(ensure_unprotected(&state, 42, Some(true)).is_ok());
+    }
+
+    #[test]
+    fn test_audited_records_outcome() {
+        let dir = std::env::temp_dir().join(format!("pm-audit-actions-{}", std::process::id()));
+        let mut state = state_with(42, Some(1000));
+        state.audit = Some(crate::audit::AuditLog::new(&dir));
+        let shared: SharedState = Arc::new(Mutex::new(state));
+
+        let ok = audited(&shared, 42, "set_priority", "nice=5".to_string(), || Ok(()));
+        assert!(ok.is_ok());
+        let failed: Result<(), AppError> =
+            audited(&shared, 42, "kill_process", "mode=Kill".to_string(), || {
+                Err(AppError::NotFound { pid: 42 })
+            });
+        assert!(failed.is_err());
+
+        let log = shared.lock().audit.clone().unwrap();
+        let entries = log.read(&AuditQuery::default()).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].action, "kill_process");
+        assert_eq!(entries[0].actor, "alice");
+        assert_eq!(entries[0].start_time, Some(1000));
+        assert_eq!(entries[0].name.as_deref(), Some("proc"));
+        assert!(matches!(
+            entries[0].outcome,
+            AuditOutcome::Failed {
+                error: AppError::NotFound { pid: 42 }
+            }
+        ));
+        assert_eq!(entries[1].detail, "nice=5");
+        assert!(matches!(entries[1].outcome, AuditOutcome::Success));
+    }
+
+    #[test]
+    fn test_matching_targets() {
+        let mut state = state_with(30, None);
+        for (pid, name) in [
+            (20, "renderer"),
+            (10, "renderer"),
+            (std::process::id(), "self"),
+        ] {
+            let mut p = state.snapshot[&30].clone();
+            p.pid = pid;
+            p.name = name.to_string();
+            state.snapshot.insert(pid, p);
+        }
+        let filter = ProcessFilter {
+            search: Some("render".to_string()),
+            ..Default::default()
+        };
+        let pids: Vec<u32> = matching_targets(&state, &filter)
+            .unwrap()
+            .iter()
+            .map(|p| p.pid)
+            .collect();
+        assert_eq!(pids, vec![10, 20]);
+
+        // The manager never targets itself, whatever the filter says.
+        let filter = ProcessFilter {
+            status: Some(ProcessStatus::Running),
+            ..Default::default()
+        };
+        let targets = matching_targets(&state, &filter).unwrap();
+        assert!(targets.iter().all(|p| p.pid != std::process::id()));
+        assert_eq!(targets.len(), 3);
+
+        assert!(matches!(
+            matching_targets(&state, &ProcessFilter::default()),
+            Err(AppError::InvalidFilter { .. })
+        ));
+    }
+
+    #[test]
+    fn test_kill_candidates_include_protected() {
+        let mut state = state_with(30, None);
+        let mut p = state.snapshot[&30].clone();
+        p.pid = 31;
+        p.name = "guarded".to_string();
+        state.snapshot.insert(31, p);
+        state
+            .protection
+            .set_custom(vec![ProtectionRule::Name("guarded".to_string())])
+            .unwrap();
+        let filter = ProcessFilter {
+            status: Some(ProcessStatus::Running),
+            ..Default::default()
+        };
+
+        let candidates = kill_candidates(&state, &filter, None).unwrap();
+        let summary: Vec<(u32, bool)> = candidates
+            .iter()
+            .map(|c| (c.process.pid, c.protected.is_some()))
+            .collect();
+        assert_eq!(summary, vec![(30, false), (31, true)]);
+
+        let forced = kill_candidates(&state, &filter, Some(true)).unwrap();
+        assert!(forced.iter().all(|c| c.protected.is_none()));
+    }
+
+    #[cfg(target_os = "linux")]
+    mod linux {
+        use super::*;
+        use crate::collector::{procfs_collector::read_state, PROC_ROOT};
+        use std::process::{Child, Command};
+
+        fn spawn(script: &str) -> Child {
+            Command::new("sh").arg("-c").arg(script).spawn().unwrap()
+        }
+
+        async fn run_graceful(
+            state: &SharedState,
+            child: &Child,
+            grace_ms: u64,
+        ) -> (KillStage, Vec<KillStage>) {
+            // Give the shell a moment to install its trap before signalling.
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            let handle = ProcessHandle::open(child.id(), None).unwrap();
+            handle.signal(libc::SIGTERM).unwrap();
+
+            let stages = std::sync::Mutex::new(Vec::new());
+            let grace = Duration::from_millis(grace_ms);
+            let last = escalate_after_grace(state, &handle, grace, |e| {
+                stages.lock().unwrap().push(e.stage)
+            })
+            .await;
+            (last, stages.into_inner().unwrap())
+        }
+
+        #[test]
+        fn test_spawn_child_applies_options() {
+            let out = std::env::temp_dir().join(format!("pm-spawn-{}.log", std::process::id()));
+            let options = SpawnOptions {
+                program: "sh".to_string(),
+                args: vec!["-c".to_string(), "echo $PM_TEST; pwd; nice".to_string()],
+                cwd: Some("/".to_string()),
+                env: [("PM_TEST".to_string(), Some("hello".to_string()))].into(),
+                nice: Some(7),
+                stdout_path: Some(out.to_string_lossy().to_string()),
+                stderr_path: None,
+                ..Default::default()
+            };
+            let status = spawn_child(&options).unwrap().wait().unwrap();
+            let output = std::fs::read_to_string(&out).unwrap();
+            std::fs::remove_file(&out).unwrap();
+
+            assert!(status.success());
+            assert_eq!(output.lines().collect::<Vec<_>>(), vec!["hello", "/", "7"]);
+        }
+
+        #[test]
+        fn test_spawn_child_reports_missing_program() {
+            let options = SpawnOptions {
+                program: "/nonexistent/pm-test-binary".to_string(),
+                ..Default::default()
+            };
+            assert!(matches!(
+                spawn_child(&options),
+                Err(AppError::SpawnFailed { .. })
+            ));
+        }
+
+        #[test]
+        fn test_stop_and_continue() {
+            let mut child = spawn("exec sleep 30");
+            let handle = ProcessHandle::open(child.id(), None).unwrap();
+            let state_of = || {
+                read_state(std::path::Path::new(PROC_ROOT), child.id())
+                    .unwrap()
+                    .0
+            };
+
+            handle.signal(libc::SIGSTOP).unwrap();
+            std::thread::sleep(Duration::from_millis(100));
+            assert_eq!(state_of(), 'T');
+
+            handle.signal(libc::SIGCONT).unwrap();
+            std::thread::sleep(Duration::from_millis(100));
+            assert_eq!(state_of(), 'S');
+
+            handle.signal(libc::SIGKILL).unwrap();
+            child.wait().unwrap();
+        }
+
+        #[tokio::test]
+        async fn test_graceful_exits_on_sigterm() {
+            let mut child = spawn("exec sleep 30");
+            let state = Arc::new(Mutex::new(AppState::new("alice".to_string())));
+            let (last, stages) = run_graceful(&state, &child, 2000).await;
+            child.wait().unwrap();
+            assert_eq!(last, KillStage::ExitedAfterTerminate);
+            assert_eq!(
+                stages,
+                vec![KillStage::TerminateSent, KillStage::ExitedAfterTerminate]
+            );
+        }
+
+        #[tokio::test]
+        async fn test_graceful_escalates_to_sigkill() {
+            // SIG_IGN survives exec, so `sleep` ignores SIGTERM.
+            let mut child = spawn("trap '' TERM; exec sleep 30");
+            let dir = std::env::temp_dir().join(format!("pm-escalation-{}", std::process::id()));
+            let mut state = state_with(child.id(), None);
+            state.audit = Some(crate::audit::AuditLog::new(&dir));
+            let state: SharedState = Arc::new(Mutex::new(state));
+
+            let (last, stages) = run_graceful(&state, &child, 300).await;
+            child.wait().unwrap();
+            assert_eq!(last, KillStage::ExitedAfterKill);
+            assert_eq!(
+                stages,
+                vec![
+                    KillStage::TerminateSent,
+                    KillStage::KillSent,
+                    KillStage::ExitedAfterKill
+                ]
+            );
+
+            let log = state.lock().audit.clone().unwrap();
+            let entries = log.read(&AuditQuery::default()).unwrap();
+            std::fs::remove_dir_all(&dir).unwrap();
+            assert_eq!(entries.len(), 1);
+            assert_eq!(entries[0].action, "kill_escalation");
+            assert_eq!(entries[0].detail, "signal=SIGKILL grace_ms=300");
+            assert_eq!(entries[0].name.as_deref(), Some("proc"));
+            assert!(matches!(entries[0].outcome, AuditOutcome::Success));
+        }
+    }
+}
@@ -1,5 +1,7 @@
 pub mod actions;
 pub mod processes;
+pub mod system;
 
 pub use actions::*;
 pub use processes::*;
+pub use system::*;
@@ -1,27 +1,37 @@
+#[cfg(target_os = "linux")]
+use std::path::Path;
 use std::sync::Arc;
 
 use parking_lot::Mutex;
 use tauri::State;
 use tracing::debug;
 
+#[cfg(target_os = "linux")]
+use crate::{affinity, collector::procfs_collector::read_details, priority};
 use crate::{
     error::AppError,
-    models::{apply_sort, ProcessDetails, ProcessDto, ProcessFilter, SortSpec},
+    history::DEFAULT_HISTORY_WINDOW_SECS,
+    models::{
+        apply_sort, build_process_tree, HistorySample, ProcessDetails, ProcessDto, ProcessFilter,
+        ProcessTreeNode, SortDirection, SortField, SortSpec,
+    },
     state::AppState,
 };
 
 type SharedState = Arc<Mutex<AppState>>;
 
 /// Return all processes (filtered and sorted server-side).
+/// `sort` keys apply in order; the default is CPU descending.
 #[tauri::command]
 pub fn list_processes(
     filter: Option<ProcessFilter>,
-    sort: Option<SortSpec>,
+    sort: Option<Vec<SortSpec>>,
     state: State<'_, SharedState>,
 ) -> Result<Vec<ProcessDto>, AppError> {
+    let mut filter = filter.unwrap_or_default();
+    filter.compile()?;
     let s = state.lock();
     let current_user = s.current_user.clone();
-    let filter = filter.unwrap_or_default();
 
     let mut result: Vec<ProcessDto> = s
         .snapshot
@@ -30,25 +40,48 @@ pub fn list_processes(
         .cloned()
         .collect();
 
-    if let Some(sort_spec) = sort {
-        apply_sort(&mut result, &sort_spec);
-    } else {
-        // Default: sort by CPU descending
-        result.sort_by(|a, b| {
-            b.cpu_percent
-                .partial_cmp(&a.cpu_percent)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-    }
+    let sort = sort.unwrap_or_else(|| {
+        vec![SortSpec {
+            field: SortField::CpuPercent,
+            direction: SortDirection::Desc,
+        }]
+    });
+    apply_sort(&mut result, &sort);
 
     debug!("list_processes: returning {} entries", result.len());
     Ok(result)
 }
 
+/// Return the process hierarchy (filtered server-side, siblings sorted).
+/// Siblings default to ascending PID order.
+#[tauri::command]
+pub fn process_tree(
+    filter: Option<ProcessFilter>,
+    sort: Option<Vec<SortSpec>>,
+    state: State<'_, SharedState>,
+) -> Result<Vec<ProcessTreeNode>, AppError> {
+    let mut filter = filter.unwrap_or_default();
+    filter.compile()?;
+    let s = state.lock();
+    let sort = sort.unwrap_or_else(|| {
+        vec![SortSpec {
+            field: SortField::Pid,
+            direction: SortDirection::Asc,
+        }]
+    });
+
+    let tree = build_process_tree(s.snapshot.values(), &filter, &s.current_user, &sort);
+
+    debug!("process_tree: returning {} roots", tree.len());
+    Ok(tree)
+}
+
 /// Return detailed information for a single process.
+/// Per-thread CPU affinity is only read when `thread_affinity` is true.
 #[tauri::command]
 pub fn process_details(
     pid: u32,
+    thread_affinity: Option<bool>,
     state: State<'_, SharedState>,
 ) -> Result<ProcessDetails, AppError> {
     let s = state.lock();
@@ -58,15 +91,64 @@ pub fn process_details(
         .cloned()
         .ok_or(AppError::NotFound { pid })?;
 
-    Ok(ProcessDetails {
-        dto,
-        threads: None,          // sysinfo does not expose this easily
-        virtual_memory_bytes: None,
-        disk_read_bytes: None,
-        disk_written_bytes: None,
-        open_files_count: None,
-        environment: None,
-    })
+    #[cfg(target_os = "linux")]
+    {
+        let proc_root = s.proc_root.clone();
+        drop(s);
+        let threads = thread_affinity == Some(true);
+        Ok(linux_details(&proc_root, dto, threads))
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        drop(s);
+        let _ = thread_affinity;
+        Ok(ProcessDetails {
+            dto,
+            threads: None,
+            virtual_memory_bytes: None,
+            disk_read_bytes: None,
+            disk_written_bytes: None,
+            open_files_count: None,
+            environment: None,
+            realtime_priority: None,
+            resource_limits: None,
+            io_priority: None,
+            cpu_affinity: None,
+            thread_affinity: None,
+            restricted: Vec::new(),
+        })
+    }
+}
+
+/// Details of `dto` read from `proc_root`, plus the syscall-only fields.
+#[cfg(target_os = "linux")]
+fn linux_details(proc_root: &Path, dto: ProcessDto, thread_affinity: bool) -> ProcessDetails {
+    let pid = dto.pid;
+    let mut details = read_details(proc_root, dto);
+    details.io_priority = priority::get_io_priority(pid);
+    details.cpu_affinity = affinity::get_affinity(pid).ok();
+    if thread_affinity {
+        details.thread_affinity = Some(affinity::thread_affinities(proc_root, pid));
+    }
+    details
+}
+
+/// Return recorded resource samples for a process, oldest first.
+/// `window_secs` defaults to the last 60 seconds.
+#[tauri::command]
+pub fn process_history(
+    pid: u32,
+    window_secs: Option<u64>,
+    state: State<'_, SharedState>,
+) -> Result<Vec<HistorySample>, AppError> {
+    let window_ms = window_secs
+        .unwrap_or(DEFAULT_HISTORY_WINDOW_SECS)
+        .saturating_mul(1000);
+    state
+        .lock()
+        .history
+        .window(pid, window_ms)
+        .ok_or(AppError::NotFound { pid })
 }
 
 /// Update the auto-refresh interval (200 – 10_000 ms).
@@ -88,3 +170,35 @@ pub fn set_paused(paused: bool, state: State<'_, SharedState>) -> Result<(), App
     debug!("Auto-refresh paused: {}", paused);
     Ok(())
 }
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::collector::{fake_proc::FakeProc, ProcessCollector};
+
+    #[test]
+    fn test_details_read_from_proc_root() {
+        let fake = FakeProc::new();
+        // A PID no live process is likely to have, so syscalls find nothing.
+        fake.add(4_000_000, "server", 'S', 1, 1000, 0, 10, 1);
+        std::fs::write(
+            fake.proc_root().join("4000000").join("environ"),
+            "HOME=/home/alice\0",
+        )
+        .unwrap();
+        let dto = fake.collector().collect().remove(&4_000_000).unwrap();
+
+        let details = linux_details(&fake.proc_root(), dto, true);
+        assert_eq!(details.disk_read_bytes, Some(4096));
+        assert_eq!(
+            details.environment,
+            Some(vec!["HOME=/home/alice".to_string()])
+        );
+        assert_eq!(details.cpu_affinity, None);
+        assert_eq!(details.thread_affinity, Some(Vec::new()));
+    }
+}
@@ -0,0 +1,14 @@
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use tauri::State;
+
+use crate::{error::AppError, models::SystemSnapshot, state::AppState};
+
+type SharedState = Arc<Mutex<AppState>>;
+
+/// Return the latest system-wide metrics (None before the first updater tick).
+#[tauri::command]
+pub fn system_overview(state: State<'_, SharedState>) -> Result<Option<SystemSnapshot>, AppError> {
+    Ok(state.lock().system.clone())
+}
@@ -19,4 +19,56 @@ pub enum AppError {
 
     #[error("Feature not supported on this OS: {feature}")]
     Unsupported { feature: String },
+
+    #[error("Invalid signal: {signal}")]
+    InvalidSignal { signal: String },
+
+    #[error("Invalid priority: {value}")]
+    InvalidPriority { value: i32 },
+
+    #[error("Invalid CPU affinity: {message}")]
+    InvalidAffinity { message: String },
+
+    #[error("Invalid resource limit: {message}")]
+    InvalidLimit { message: String },
+
+    #[error("Failed to start {program}: {message}")]
+    SpawnFailed { program: String, message: String },
+
+    #[error("Invalid filter: {message}")]
+    InvalidFilter { message: String },
+
+    #[error("Invalid search pattern {pattern:?}: {message}")]
+    InvalidRegex { pattern: String, message: String },
+
+    /// The target is on the protection list; the action must be forced.
+    #[error("Process {pid} is protected ({reason})")]
+    Protected { pid: u32, reason: String },
+
+    /// The PID now belongs to a different process than the one the user acted on.
+    #[error("Process {pid} has been replaced (expected start time {expected_start_time}, found {actual_start_time})")]
+    IdentityMismatch {
+        pid: u32,
+        expected_start_time: u64,
+        actual_start_time: u64,
+    },
+}
+
+impl AppError {
+    /// Append `note` to an error raised after part of an action already took
+    /// effect, e.g. when only some threads of a process were updated.
+    pub fn partial(self, note: &str) -> Self {
+        match self {
+            Self::PermissionDenied { pid, message } => Self::PermissionDenied {
+                pid,
+                message: format!("{message}; {note}"),
+            },
+            Self::InvalidAffinity { message } => Self::InvalidAffinity {
+                message: format!("{message}; {note}"),
+            },
+            other => Self::OsError {
+                message: format!("{other}; {note}"),
+            },
+        }
+    }
 }
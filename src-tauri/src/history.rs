@@ -0,0 +1,178 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::models::{HistorySample, ProcessDto};
+
+/// Samples kept per PID — 60 s at the fastest (200 ms) refresh interval.
+pub const HISTORY_CAPACITY: usize = 300;
+
+/// Window returned by `process_history` when the caller does not pass one.
+pub const DEFAULT_HISTORY_WINDOW_SECS: u64 = 60;
+
+/// Bounded per-PID time series fed from the updater.
+pub struct ProcessHistory {
+    capacity: usize,
+    series: HashMap<u32, Series>,
+}
+
+struct Series {
+    /// Start time of the process the samples belong to; a change means the PID was reused.
+    start_time: Option<u64>,
+    /// Cumulative disk counters from the last sample, used to derive rates.
+    last_disk: (Option<u64>, Option<u64>),
+    samples: VecDeque<HistorySample>,
+}
+
+impl ProcessHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            series: HashMap::new(),
+        }
+    }
+
+    /// Append one sample per process in `snapshot` and drop series for PIDs that are gone.
+    pub fn record(&mut self, snapshot: &HashMap<u32, ProcessDto>, timestamp_ms: u64) {
+        self.series.retain(|pid, _| snapshot.contains_key(pid));
+
+        for (pid, p) in snapshot {
+            let series = self.series.entry(*pid).or_insert_with(|| Series {
+                start_time: p.start_time,
+                last_disk: (None, None),
+                samples: VecDeque::with_capacity(self.capacity),
+            });
+            if series.start_time != p.start_time {
+                series.start_time = p.start_time;
+                series.last_disk = (None, None);
+                series.samples.clear();
+            }
+
+            let elapsed_ms = series
+                .samples
+                .back()
+                .map(|last| timestamp_ms.saturating_sub(last.timestamp_ms));
+            let sample = HistorySample {
+                timestamp_ms,
+                cpu_percent: p.cpu_percent,
+                memory_bytes: p.memory_bytes,
+                disk_read_bytes_per_sec: rate(series.last_disk.0, p.disk_read_bytes, elapsed_ms),
+                disk_written_bytes_per_sec: rate(
+                    series.last_disk.1,
+                    p.disk_written_bytes,
+                    elapsed_ms,
+                ),
+                threads: p.threads,
+            };
+            series.last_disk = (p.disk_read_bytes, p.disk_written_bytes);
+
+            if series.samples.len() == self.capacity {
+                series.samples.pop_front();
+            }
+            series.samples.push_back(sample);
+        }
+    }
+
+    /// Samples for `pid` no older than `window_ms` before the newest one, oldest first.
+    /// Returns `None` if nothing has been recorded for the PID.
+    pub fn window(&self, pid: u32, window_ms: u64) -> Option<Vec<HistorySample>> {
+        let samples = &self.series.get(&pid)?.samples;
+        let newest = samples.back().map(|s| s.timestamp_ms).unwrap_or(0);
+        let cutoff = newest.saturating_sub(window_ms);
+        Some(
+            samples
+                .iter()
+                .filter(|s| s.timestamp_ms >= cutoff)
+                .cloned()
+                .collect(),
+        )
+    }
+}
+
+impl Default for ProcessHistory {
+    fn default() -> Self {
+        Self::new(HISTORY_CAPACITY)
+    }
+}
+
+fn rate(prev: Option<u64>, current: Option<u64>, elapsed_ms: Option<u64>) -> Option<u64> {
+    match (prev, current, elapsed_ms) {
+        (Some(prev), Some(current), Some(ms)) if ms > 0 => {
+            Some(current.saturating_sub(prev) * 1000 / ms)
+        }
+        _ => None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_proc(pid: u32, cpu: f32, start_time: u64, disk_read: u64) -> ProcessDto {
+        ProcessDto {
+            cpu_percent: cpu,
+            memory_bytes: 1024,
+            start_time: Some(start_time),
+            threads: Some(2),
+            disk_read_bytes: Some(disk_read),
+            disk_written_bytes: Some(0),
+            ..ProcessDto::test(pid, &format!("proc{}", pid))
+        }
+    }
+
+    fn snapshot(procs: Vec<ProcessDto>) -> HashMap<u32, ProcessDto> {
+        procs.into_iter().map(|p| (p.pid, p)).collect()
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut history = ProcessHistory::new(3);
+        for tick in 0..5u64 {
+            history.record(
+                &snapshot(vec![make_proc(1, tick as f32, 0, 0)]),
+                tick * 1000,
+            );
+        }
+        let samples = history.window(1, u64::MAX).unwrap();
+        assert_eq!(samples.len(), 3);
+        assert_eq!(samples[0].cpu_percent, 2.0);
+        assert_eq!(samples[2].cpu_percent, 4.0);
+    }
+
+    #[test]
+    fn test_history_disk_rate() {
+        let mut history = ProcessHistory::default();
+        history.record(&snapshot(vec![make_proc(1, 0.0, 0, 1000)]), 0);
+        history.record(&snapshot(vec![make_proc(1, 0.0, 0, 5000)]), 2000);
+        let samples = history.window(1, u64::MAX).unwrap();
+        assert_eq!(samples[0].disk_read_bytes_per_sec, None);
+        assert_eq!(samples[1].disk_read_bytes_per_sec, Some(2000));
+        assert_eq!(samples[1].disk_written_bytes_per_sec, Some(0));
+    }
+
+    #[test]
+    fn test_history_drops_vanished_and_reused_pids() {
+        let mut history = ProcessHistory::default();
+        history.record(
+            &snapshot(vec![make_proc(1, 0.0, 10, 0), make_proc(2, 0.0, 10, 0)]),
+            0,
+        );
+        history.record(&snapshot(vec![make_proc(1, 0.0, 99, 0)]), 1000);
+        assert!(history.window(2, u64::MAX).is_none());
+        // PID 1 now belongs to a different process; old samples are discarded.
+        assert_eq!(history.window(1, u64::MAX).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_history_window_filters_old_samples() {
+        let mut history = ProcessHistory::default();
+        for tick in 0..10u64 {
+            history.record(&snapshot(vec![make_proc(1, 0.0, 0, 0)]), tick * 1000);
+        }
+        let samples = history.window(1, 3000).unwrap();
+        assert_eq!(samples.len(), 4);
+        assert_eq!(samples[0].timestamp_ms, 6000);
+    }
+}
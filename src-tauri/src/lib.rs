@@ -1,17 +1,31 @@
+#[cfg(target_os = "linux")]
+pub mod affinity;
+pub mod audit;
+pub mod classify;
 pub mod collector;
 pub mod commands;
 pub mod error;
+pub mod history;
+#[cfg(target_os = "linux")]
+pub mod limits;
 pub mod models;
+#[cfg(unix)]
+pub mod priority;
+#[cfg(unix)]
+pub mod process_handle;
+pub mod protection;
 pub mod state;
 pub mod updater;
 
 use std::sync::Arc;
 
 use parking_lot::Mutex;
-use tracing::info;
+use tauri::Manager;
+use tracing::{info, warn};
 use tracing_subscriber::EnvFilter;
 
-use collector::SysinfoCollector;
+use audit::AuditLog;
+use collector::{CollectorKind, SysinfoCollector};
 use commands::*;
 use state::AppState;
 
@@ -60,21 +74,59 @@ pub fn run() {
     let app_state: Arc<Mutex<AppState>> = Arc::new(Mutex::new(AppState::new(current_user)));
     let state_for_updater = Arc::clone(&app_state);
 
+    let collector_kind = CollectorKind::from_env();
+    info!("Using {:?} collector", collector_kind);
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_clipboard_manager::init())
         .manage(app_state)
         .setup(|app| {
+            match app.path().app_data_dir() {
+                Ok(dir) => {
+                    let log = AuditLog::new(&dir);
+                    info!("Audit log: {}", log.path().display());
+                    let mut state = state_for_updater.lock();
+                    state.audit = Some(log);
+                    if let Err(e) = state.protection.load(&dir) {
+                        warn!("Cannot load protection rules from {}: {}", dir.display(), e);
+                    }
+                }
+                Err(e) => warn!(
+                    "No app data directory, audit log and saved protection rules disabled: {}",
+                    e
+                ),
+            }
+
             let handle = app.handle().clone();
-            updater::start_updater(handle, state_for_updater);
+            updater::start_updater(handle, state_for_updater, collector_kind.build());
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             list_processes,
+            process_tree,
             process_details,
+            process_history,
             set_refresh_interval,
             set_paused,
+            system_overview,
             kill_process,
+            kill_tree,
+            kill_matching,
+            set_kill_grace_period,
+            list_protection_rules,
+            set_protection_rules,
+            list_audit_log,
+            send_signal,
+            suspend_process,
+            resume_process,
+            set_priority,
+            set_affinity,
+            set_io_priority,
+            set_rlimit,
+            set_oom_score_adj,
+            spawn_process,
+            restart_process,
             open_path,
             copy_to_clipboard,
         ])
@@ -0,0 +1,159 @@
+use std::{fs, io, path::Path, ptr};
+
+use crate::{error::AppError, models::RlimitResource};
+
+/// Change one resource limit of `pid` with `prlimit(2)`. `None` means unlimited.
+///
+/// Raising a hard limit needs `CAP_SYS_RESOURCE`; lowering one is permanent
+/// for unprivileged callers.
+pub fn set_rlimit(
+    pid: u32,
+    resource: RlimitResource,
+    soft: Option<u64>,
+    hard: Option<u64>,
+) -> Result<(), AppError> {
+    let limit = |v: Option<u64>| v.unwrap_or(libc::RLIM_INFINITY);
+    if limit(soft) > limit(hard) {
+        return Err(AppError::InvalidLimit {
+            message: "Soft limit exceeds hard limit".to_string(),
+        });
+    }
+
+    let resource = match resource {
+        RlimitResource::Cpu => libc::RLIMIT_CPU,
+        RlimitResource::Fsize => libc::RLIMIT_FSIZE,
+        RlimitResource::Data => libc::RLIMIT_DATA,
+        RlimitResource::Stack => libc::RLIMIT_STACK,
+        RlimitResource::Core => libc::RLIMIT_CORE,
+        RlimitResource::Rss => libc::RLIMIT_RSS,
+        RlimitResource::Nproc => libc::RLIMIT_NPROC,
+        RlimitResource::Nofile => libc::RLIMIT_NOFILE,
+        RlimitResource::Memlock => libc::RLIMIT_MEMLOCK,
+        RlimitResource::As => libc::RLIMIT_AS,
+        RlimitResource::Locks => libc::RLIMIT_LOCKS,
+        RlimitResource::Sigpending => libc::RLIMIT_SIGPENDING,
+        RlimitResource::Msgqueue => libc::RLIMIT_MSGQUEUE,
+        RlimitResource::Nice => libc::RLIMIT_NICE,
+        RlimitResource::Rtprio => libc::RLIMIT_RTPRIO,
+        RlimitResource::Rttime => libc::RLIMIT_RTTIME,
+    };
+    let new = libc::rlimit {
+        rlim_cur: limit(soft),
+        rlim_max: limit(hard),
+    };
+
+    let ret = unsafe { libc::prlimit(pid as libc::pid_t, resource, &new, ptr::null_mut()) };
+    if ret == 0 {
+        return Ok(());
+    }
+
+    let err = io::Error::last_os_error();
+    match err.raw_os_error() {
+        Some(libc::EPERM) => Err(AppError::PermissionDenied {
+            pid,
+            message: "Raising a hard limit or changing another user's limits requires elevated privileges"
+                .to_string(),
+        }),
+        Some(libc::EINVAL) => Err(AppError::InvalidLimit {
+            message: err.to_string(),
+        }),
+        Some(libc::ESRCH) => Err(AppError::NotFound { pid }),
+        _ => Err(AppError::OsError {
+            message: err.to_string(),
+        }),
+    }
+}
+
+/// Lowest `oom_score_adj`: the OOM killer never picks the process.
+pub const OOM_SCORE_ADJ_MIN: i32 = -1000;
+/// Highest `oom_score_adj`: the process is always picked first.
+pub const OOM_SCORE_ADJ_MAX: i32 = 1000;
+
+/// Write `<root>/<pid>/oom_score_adj`.
+///
+/// Going below the value the process already had needs `CAP_SYS_RESOURCE`.
+pub fn set_oom_score_adj(proc_root: &Path, pid: u32, value: i32) -> Result<(), AppError> {
+    if !(OOM_SCORE_ADJ_MIN..=OOM_SCORE_ADJ_MAX).contains(&value) {
+        return Err(AppError::InvalidLimit {
+            message: format!(
+                "oom_score_adj {value} is outside {OOM_SCORE_ADJ_MIN}..={OOM_SCORE_ADJ_MAX}"
+            ),
+        });
+    }
+
+    let path = proc_root.join(pid.to_string()).join("oom_score_adj");
+    fs::write(path, value.to_string()).map_err(|e| match e.kind() {
+        io::ErrorKind::PermissionDenied => AppError::PermissionDenied {
+            pid,
+            message: "Lowering oom_score_adj requires elevated privileges".to_string(),
+        },
+        io::ErrorKind::NotFound => AppError::NotFound { pid },
+        _ => AppError::OsError {
+            message: e.to_string(),
+        },
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    #[test]
+    fn test_set_rlimit_on_child() {
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        let pid = child.id();
+
+        set_rlimit(pid, RlimitResource::Nofile, Some(64), Some(128)).unwrap();
+        set_rlimit(pid, RlimitResource::Core, Some(0), None).unwrap();
+        let limits = std::fs::read_to_string(format!("/proc/{pid}/limits")).unwrap();
+        let row = |name: &str| {
+            limits
+                .lines()
+                .find(|l| l.starts_with(name))
+                .unwrap()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        assert_eq!(row("Max open files"), "Max open files 64 128 files");
+        assert_eq!(
+            row("Max core file size"),
+            "Max core file size 0 unlimited bytes"
+        );
+
+        assert!(matches!(
+            set_rlimit(pid, RlimitResource::Nofile, Some(256), Some(128)),
+            Err(AppError::InvalidLimit { .. })
+        ));
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+
+    #[test]
+    fn test_set_oom_score_adj() {
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        let pid = child.id();
+        let proc_root = Path::new(crate::collector::PROC_ROOT);
+
+        set_oom_score_adj(proc_root, pid, 500).unwrap();
+        let adj = std::fs::read_to_string(format!("/proc/{pid}/oom_score_adj")).unwrap();
+        assert_eq!(adj.trim(), "500");
+        assert!(matches!(
+            set_oom_score_adj(proc_root, pid, 1001),
+            Err(AppError::InvalidLimit { .. })
+        ));
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+        assert!(matches!(
+            set_oom_score_adj(proc_root, pid, 0),
+            Err(AppError::NotFound { .. })
+        ));
+    }
+}
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+/// One line of the audit log: an action taken against a process.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp_ms: u64,
+    /// User the process manager runs as
+    pub actor: String,
+    pub pid: u32,
+    /// Start time (Unix seconds), identifying the process across PID reuse
+    pub start_time: Option<u64>,
+    pub name: Option<String>,
+    pub cmd: Vec<String>,
+    /// Command that was invoked, e.g. `kill_process` or `set_priority`
+    pub action: String,
+    /// Parameters of the action, e.g. `mode=Graceful` or `nice=10`
+    pub detail: String,
+    pub outcome: AuditOutcome,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum AuditOutcome {
+    Success,
+    Failed { error: AppError },
+}
+
+/// Filter for `list_audit_log`. Entries are returned newest first.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct AuditQuery {
+    pub pid: Option<u32>,
+    /// Exact action name
+    pub action: Option<String>,
+    /// Exact actor username
+    pub actor: Option<String>,
+    /// Only entries at or after this time (ms since epoch)
+    pub since_ms: Option<u64>,
+    /// Maximum number of entries (default 500)
+    pub limit: Option<usize>,
+}
+
+impl AuditQuery {
+    pub fn matches(&self, e: &AuditEntry) -> bool {
+        if self.pid.is_some() && self.pid != Some(e.pid) {
+            return false;
+        }
+        if self.action.is_some() && self.action.as_deref() != Some(e.action.as_str()) {
+            return false;
+        }
+        if self.actor.is_some() && self.actor.as_deref() != Some(e.actor.as_str()) {
+            return false;
+        }
+        if let Some(since) = self.since_ms {
+            if e.timestamp_ms < since {
+                return false;
+            }
+        }
+        true
+    }
+}
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+/// One point in a process's resource history, recorded on each updater tick.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HistorySample {
+    pub timestamp_ms: u64,
+    pub cpu_percent: f32,
+    /// Resident set size in bytes
+    pub memory_bytes: u64,
+    /// Storage read rate since the previous sample (None for the first sample)
+    pub disk_read_bytes_per_sec: Option<u64>,
+    /// Storage write rate since the previous sample (None for the first sample)
+    pub disk_written_bytes_per_sec: Option<u64>,
+    pub threads: Option<u32>,
+}
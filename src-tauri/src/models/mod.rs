@@ -1,3 +1,17 @@
+pub mod audit;
+pub mod history;
 pub mod process;
+pub mod protection;
+pub mod query;
+pub mod signal;
+pub mod system;
+pub mod tree;
 
+pub use audit::*;
+pub use history::*;
 pub use process::*;
+pub use protection::*;
+pub use query::*;
+pub use signal::*;
+pub use system::*;
+pub use tree::*;
@@ -1,13 +1,73 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::HashMap,
+};
+
+use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
 
-/// Process status mirrored from sysinfo.
+use super::query::Query;
+use crate::error::AppError;
+
+/// Scheduler state of a process, from procfs or sysinfo.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "lowercase")]
+#[serde(rename_all = "snake_case")]
 pub enum ProcessStatus {
     Running,
+    /// Interruptible sleep, waiting for an event (S)
     Sleeping,
+    /// Idle kernel thread (I)
+    Idle,
+    /// Uninterruptible sleep, usually blocked on I/O (D)
+    DiskSleep,
+    /// Stopped by a job-control signal (T)
     Stopped,
+    /// Stopped by a debugger (t)
+    TracingStop,
     Zombie,
+    /// Exiting, about to be reaped (X)
+    Dead,
+    /// Uninterruptible sleep that a fatal signal can still end (K)
+    Wakekill,
+    /// Parked kernel thread (P)
+    Parked,
+    /// Waiting on a lock (BSD)
+    LockBlocked,
+    Unknown,
+}
+
+impl ProcessStatus {
+    /// Position when sorting by status: running first, then blocked, sleeping,
+    /// stopped and exiting states, unknown last.
+    pub fn sort_rank(&self) -> u8 {
+        match self {
+            ProcessStatus::Running => 0,
+            ProcessStatus::DiskSleep => 1,
+            ProcessStatus::Wakekill => 2,
+            ProcessStatus::LockBlocked => 3,
+            ProcessStatus::Sleeping => 4,
+            ProcessStatus::Idle => 5,
+            ProcessStatus::Parked => 6,
+            ProcessStatus::Stopped => 7,
+            ProcessStatus::TracingStop => 8,
+            ProcessStatus::Zombie => 9,
+            ProcessStatus::Dead => 10,
+            ProcessStatus::Unknown => 11,
+        }
+    }
+}
+
+/// Who a process belongs to, as decided by `classify::Classifier`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessKind {
+    /// Kernel thread (Linux)
+    Kernel,
+    /// Service or daemon: a system cgroup slice or a non-login account
+    System,
+    /// Owned by a login account
+    User,
+    /// Owner could not be determined
     Unknown,
 }
 
@@ -17,6 +77,10 @@ pub struct ProcessDto {
     pub pid: u32,
     pub name: String,
     pub status: ProcessStatus,
+    /// When the process was first seen in its current status (ms since
+    /// epoch). Set by the updater; time before the manager saw the process
+    /// is not counted.
+    pub status_since_ms: Option<u64>,
     /// Cumulative CPU percentage (0.0–100.0, per-core normalised by sysinfo)
     pub cpu_percent: f32,
     /// Resident set size in bytes
@@ -25,26 +89,79 @@ pub struct ProcessDto {
     pub user: Option<String>,
     /// Full path to the executable (None for kernel threads or restricted)
     pub path: Option<String>,
+    /// Working directory (None when restricted)
+    pub cwd: Option<String>,
     /// Parent process ID
     pub parent_pid: Option<u32>,
     /// Unix start timestamp (seconds); None if unavailable  
     pub start_time: Option<u64>,
     /// True when the process is owned by a different user or root
     pub needs_elevation: bool,
+    /// Kernel thread, system service or user process
+    pub kind: ProcessKind,
     /// Command-line arguments
     pub cmd: Vec<String>,
+    /// Number of threads (None if unavailable on this OS)
+    pub threads: Option<u32>,
+    /// Total bytes read from storage since the process started
+    pub disk_read_bytes: Option<u64>,
+    /// Total bytes written to storage since the process started
+    pub disk_written_bytes: Option<u64>,
+    /// Nice value (-20 highest priority … 19 lowest; None on Windows)
+    pub nice: Option<i32>,
+    /// CPU scheduling policy (Linux only)
+    pub scheduling_policy: Option<SchedulingPolicy>,
+    /// Kernel OOM-killer badness (0–2000, higher is killed first; Linux only)
+    pub oom_score: Option<u32>,
+    /// User adjustment to `oom_score` (-1000 never kill … 1000; Linux only)
+    pub oom_score_adj: Option<i32>,
+    /// True for processes started through `spawn_process`
+    pub launched_by_app: bool,
+}
+
+/// Linux CPU scheduling policy (`SCHED_*`).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SchedulingPolicy {
+    /// SCHED_OTHER, the default time-sharing policy
+    Other,
+    Batch,
+    Idle,
+    Fifo,
+    RoundRobin,
+    Deadline,
+}
+
+/// How `ProcessFilter::search` is compared. Every mode but `Exact` ignores case.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchMode {
+    #[default]
+    Substring,
+    /// Regular expression, matching anywhere in a field
+    Regex,
+    /// Shell glob (`*`, `?`, `[abc]`, `[!abc]`) matching a whole field
+    Glob,
+    /// Whole-field equality, case included
+    Exact,
 }
 
 /// Filter applied server-side before returning results.
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ProcessFilter {
-    /// Substring match against name, path or stringified PID
+    /// Matched against name, path, PID, user, cwd, the full command line and
+    /// each of its arguments
     pub search: Option<String>,
+    #[serde(default)]
+    pub search_mode: SearchMode,
+    /// `search` compiled by `compile` in regex and glob modes
+    #[serde(skip)]
+    pub search_regex: Option<Regex>,
     /// Exact username match
     pub user: Option<String>,
     /// If true, only return processes owned by current user
     pub mine_only: Option<bool>,
-    /// If true, return only system processes (pid < 500 heuristic + root owned)
+    /// If true, return only kernel threads and system processes
     pub system_only: Option<bool>,
     /// If true, exclude system processes
     pub non_system_only: Option<bool>,
@@ -54,6 +171,11 @@ pub struct ProcessFilter {
     pub cpu_gt: Option<f32>,
     /// Minimum memory threshold in bytes (inclusive)
     pub memory_gt_bytes: Option<u64>,
+    /// Filter expression, e.g. `cpu > 20 and (name ~ /java/ or cmd contains "--port")`
+    pub query: Option<String>,
+    /// `query` parsed by `compile`
+    #[serde(skip)]
+    pub compiled: Option<Query>,
 }
 
 /// Column names that can be used for sorting.
@@ -67,6 +189,17 @@ pub enum SortField {
     User,
     Status,
     StartTime,
+    OomScore,
+    ParentPid,
+    Path,
+    /// Full command line
+    Cmd,
+    Threads,
+    Nice,
+    DiskReadBytes,
+    DiskWrittenBytes,
+    /// Time since start; ascending puts the youngest process first
+    Age,
 }
 
 /// Sort order.
@@ -92,6 +225,100 @@ pub enum KillMode {
     Terminate,
     /// Forceful kill (SIGKILL on Unix, TerminateProcess immediate on Windows)
     Kill,
+    /// SIGTERM, then SIGKILL if the process outlives the grace period
+    /// (on Windows TerminateProcess already ends it in the first stage)
+    Graceful,
+}
+
+/// Stage reached by a `KillMode::Graceful` request, reported via `kill:progress`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KillStage {
+    /// SIGTERM delivered; waiting for the process to exit
+    TerminateSent,
+    /// Grace period expired; SIGKILL delivered
+    KillSent,
+    /// Process exited within the grace period
+    ExitedAfterTerminate,
+    /// Process exited after SIGKILL
+    ExitedAfterKill,
+    /// Process survived SIGKILL (e.g. stuck in uninterruptible sleep)
+    StillRunning,
+    /// Sending SIGKILL failed; see `error`
+    Failed,
+}
+
+/// Progress event for a graceful terminate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KillProgressEvent {
+    pub pid: u32,
+    pub stage: KillStage,
+    /// Time since SIGTERM was sent
+    pub elapsed_ms: u64,
+    pub grace_period_ms: u64,
+    pub error: Option<AppError>,
+}
+
+/// Order in which `kill_tree` signals a process and its descendants.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TreeKillOrder {
+    /// Deepest descendants first, the root last
+    #[default]
+    ChildrenFirst,
+    /// The root first, so it cannot respawn children, then each level below it
+    ParentFirst,
+}
+
+/// Parameters for `spawn_process`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SpawnOptions {
+    /// Executable name (looked up in `PATH`) or path
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// `argv[0]` seen by the program, when it differs from `program` (Unix only)
+    #[serde(default)]
+    pub arg0: Option<String>,
+    /// Working directory (defaults to the manager's own)
+    pub cwd: Option<String>,
+    /// Variables set on top of the inherited environment; `None` removes one
+    #[serde(default)]
+    pub env: HashMap<String, Option<String>>,
+    /// Start from an empty environment instead of inheriting the manager's
+    #[serde(default)]
+    pub clear_env: bool,
+    /// Initial nice value (Unix only)
+    pub nice: Option<i32>,
+    /// Append stdout to this file instead of discarding it
+    pub stdout_path: Option<String>,
+    /// Append stderr to this file instead of discarding it
+    pub stderr_path: Option<String>,
+}
+
+/// Outcome of an action on one PID within a multi-process operation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessActionResult {
+    pub pid: u32,
+    pub name: String,
+    /// None on success
+    pub error: Option<AppError>,
+}
+
+/// A process matched by `kill_matching`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KillCandidate {
+    pub process: ProcessDto,
+    /// Why the process would be skipped; None if it would be signalled
+    pub protected: Option<String>,
+}
+
+/// Result of `kill_matching`: what would be signalled, or what was.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum KillMatchingResult {
+    DryRun { candidates: Vec<KillCandidate> },
+    Executed { results: Vec<ProcessActionResult> },
 }
 
 /// Incremental update event emitted by the updater task.
@@ -113,6 +340,93 @@ pub struct ProcessDetails {
     pub disk_written_bytes: Option<u64>,
     pub open_files_count: Option<usize>,
     pub environment: Option<Vec<String>>,
+    /// Static priority (1–99) under the FIFO / round-robin policies, 0 otherwise
+    pub realtime_priority: Option<u32>,
+    /// I/O scheduling class and level (Linux only)
+    pub io_priority: Option<IoPriority>,
+    /// Soft / hard resource limits, as in `/proc/<pid>/limits` (Linux only)
+    pub resource_limits: Option<Vec<ResourceLimit>>,
+    /// CPUs the main thread may run on (Linux only)
+    pub cpu_affinity: Option<Vec<usize>>,
+    /// Per-thread affinity, only filled when requested
+    pub thread_affinity: Option<Vec<ThreadAffinity>>,
+    /// Fields left empty because reading them was denied by the OS
+    pub restricted: Vec<DetailField>,
+}
+
+/// I/O scheduling class, as set by `ionice -c`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IoPriorityClass {
+    /// No class set: the kernel derives best-effort priority from the nice value
+    None,
+    Realtime,
+    BestEffort,
+    Idle,
+}
+
+/// I/O priority of a process.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct IoPriority {
+    pub class: IoPriorityClass,
+    /// 0 (highest) to 7 (lowest) within the class
+    pub level: u8,
+}
+
+/// `RLIMIT_*` resource, as accepted by `setrlimit(2)` / `prlimit(2)`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RlimitResource {
+    Cpu,
+    Fsize,
+    Data,
+    Stack,
+    Core,
+    Rss,
+    Nproc,
+    Nofile,
+    Memlock,
+    As,
+    Locks,
+    Sigpending,
+    Msgqueue,
+    Nice,
+    Rtprio,
+    Rttime,
+}
+
+/// One resource limit of a process.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ResourceLimit {
+    pub resource: RlimitResource,
+    /// None when unlimited
+    pub soft: Option<u64>,
+    /// None when unlimited
+    pub hard: Option<u64>,
+    /// Unit as reported by the kernel (`bytes`, `seconds`, ...), if any
+    pub unit: Option<String>,
+}
+
+/// Allowed CPU set of one thread.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ThreadAffinity {
+    pub tid: u32,
+    /// Thread name (`comm`)
+    pub name: String,
+    pub cpus: Vec<usize>,
+}
+
+/// Names of the optional `ProcessDetails` fields, used to report per-field failures.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DetailField {
+    Threads,
+    VirtualMemoryBytes,
+    DiskReadBytes,
+    DiskWrittenBytes,
+    OpenFilesCount,
+    Environment,
+    ResourceLimits,
 }
 
 // ---------------------------------------------------------------------------
@@ -120,19 +434,42 @@ pub struct ProcessDetails {
 // ---------------------------------------------------------------------------
 
 impl ProcessFilter {
+    /// True if the filter has no criteria and therefore matches every process.
+    pub fn is_empty(&self) -> bool {
+        self.search.as_deref().unwrap_or("").is_empty()
+            && self.user.is_none()
+            && self.mine_only != Some(true)
+            && self.system_only != Some(true)
+            && self.non_system_only != Some(true)
+            && self.status.is_none()
+            && self.cpu_gt.is_none()
+            && self.memory_gt_bytes.is_none()
+            && self.query.as_deref().unwrap_or("").trim().is_empty()
+    }
+
+    /// Parse `query` and the search pattern once so `matches` does not redo it
+    /// for every process. A malformed query fails with `InvalidFilter`, a
+    /// malformed regex or glob with `InvalidRegex`.
+    pub fn compile(&mut self) -> Result<(), AppError> {
+        self.search_regex = match self.search.as_deref() {
+            Some(q) if !q.is_empty() => search_regex(q, self.search_mode)?,
+            _ => None,
+        };
+        self.compiled = match self.query.as_deref().map(str::trim) {
+            Some(q) if !q.is_empty() => {
+                Some(Query::parse(q).map_err(|e| AppError::InvalidFilter {
+                    message: e.to_string(),
+                })?)
+            }
+            _ => None,
+        };
+        Ok(())
+    }
+
     pub fn matches(&self, p: &ProcessDto, current_user: &str) -> bool {
         // Search filter
-        if let Some(ref q) = self.search {
-            let q_lower = q.to_lowercase();
-            let pid_str = p.pid.to_string();
-            let name_match = p.name.to_lowercase().contains(&q_lower);
-            let path_match = p
-                .path
-                .as_deref()
-                .map(|pa| pa.to_lowercase().contains(&q_lower))
-                .unwrap_or(false);
-            let pid_match = pid_str.contains(&q_lower);
-            if !name_match && !path_match && !pid_match {
+        if let Some(q) = self.search.as_deref().filter(|q| !q.is_empty()) {
+            if !self.search_matches(q, p) {
                 return false;
             }
         }
@@ -144,10 +481,8 @@ impl ProcessFilter {
             }
         }
 
-        if self.mine_only == Some(true) {
-            if p.user.as_deref() != Some(current_user) {
-                return false;
-            }
+        if self.mine_only == Some(true) && p.user.as_deref() != Some(current_user) {
+            return false;
         }
 
         if self.system_only == Some(true) && !is_system(p) {
@@ -175,45 +510,204 @@ impl ProcessFilter {
             }
         }
 
-        true
+        match (&self.compiled, self.query.as_deref().map(str::trim)) {
+            (Some(query), _) => query.matches(p),
+            // Not compiled: parse here; a malformed query matches nothing.
+            (None, Some(q)) if !q.is_empty() => Query::parse(q).is_ok_and(|query| query.matches(p)),
+            _ => true,
+        }
+    }
+
+    fn search_matches(&self, q: &str, p: &ProcessDto) -> bool {
+        let pid = p.pid.to_string();
+        let cmdline = p.cmd.join(" ");
+        let fields = [
+            Some(p.name.as_str()),
+            p.path.as_deref(),
+            Some(pid.as_str()),
+            p.user.as_deref(),
+            p.cwd.as_deref(),
+            (!cmdline.is_empty()).then_some(cmdline.as_str()),
+        ];
+        let mut fields = fields
+            .into_iter()
+            .flatten()
+            .chain(p.cmd.iter().map(String::as_str));
+
+        match self.search_mode {
+            SearchMode::Substring => {
+                let q = q.to_lowercase();
+                fields.any(|f| f.to_lowercase().contains(&q))
+            }
+            SearchMode::Exact => fields.any(|f| f == q),
+            SearchMode::Regex | SearchMode::Glob => match &self.search_regex {
+                Some(re) => fields.any(|f| re.is_match(f)),
+                // Not compiled: build here; a malformed pattern matches nothing.
+                None => search_regex(q, self.search_mode)
+                    .ok()
+                    .flatten()
+                    .is_some_and(|re| fields.any(|f| re.is_match(f))),
+            },
+        }
+    }
+}
+
+/// Case-insensitive regex for the regex and glob search modes; None otherwise.
+fn search_regex(pattern: &str, mode: SearchMode) -> Result<Option<Regex>, AppError> {
+    let source = match mode {
+        SearchMode::Regex => pattern.to_string(),
+        SearchMode::Glob => glob_to_regex(pattern),
+        SearchMode::Substring | SearchMode::Exact => return Ok(None),
+    };
+    RegexBuilder::new(&source)
+        .case_insensitive(true)
+        .build()
+        .map(Some)
+        .map_err(|e| AppError::InvalidRegex {
+            pattern: pattern.to_string(),
+            message: e.to_string(),
+        })
+}
+
+/// Translate a shell glob into an anchored regex. Bracket classes are kept,
+/// with a leading `!` negating them and a `]` right after `[` or `[!` taken
+/// literally, as in `fnmatch`.
+fn glob_to_regex(glob: &str) -> String {
+    let mut out = String::from("^");
+    let mut chars = glob.chars().peekable();
+    let mut in_class = false;
+    while let Some(c) = chars.next() {
+        if in_class {
+            if c == ']' {
+                in_class = false;
+            } else if c == '[' || c == '\\' {
+                out.push('\\');
+            }
+            out.push(c);
+            continue;
+        }
+        match c {
+            '*' => out.push_str(".*"),
+            '?' => out.push('.'),
+            '[' => {
+                in_class = true;
+                out.push('[');
+                if chars.next_if_eq(&'!').is_some() {
+                    out.push('^');
+                }
+                if chars.next_if_eq(&']').is_some() {
+                    out.push_str("\\]");
+                }
+            }
+            _ => out.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
     }
+    out.push('$');
+    out
 }
 
 fn is_system(p: &ProcessDto) -> bool {
-    p.pid < 500
-        || p.user
-            .as_deref()
-            .map(|u| matches!(u, "root" | "SYSTEM" | "NT AUTHORITY\\SYSTEM"))
-            .unwrap_or(false)
-}
-
-pub fn apply_sort(processes: &mut Vec<ProcessDto>, sort: &SortSpec) {
-    processes.sort_by(|a, b| {
-        let ord = match sort.field {
-            SortField::Pid => a.pid.cmp(&b.pid),
-            SortField::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-            SortField::CpuPercent => a
-                .cpu_percent
-                .partial_cmp(&b.cpu_percent)
-                .unwrap_or(std::cmp::Ordering::Equal),
-            SortField::MemoryBytes => a.memory_bytes.cmp(&b.memory_bytes),
-            SortField::User => a
-                .user
-                .as_deref()
-                .unwrap_or("")
-                .cmp(b.user.as_deref().unwrap_or("")),
-            SortField::Status => format!("{:?}", a.status).cmp(&format!("{:?}", b.status)),
-            SortField::StartTime => a
-                .start_time
-                .unwrap_or(0)
-                .cmp(&b.start_time.unwrap_or(0)),
-        };
-        if sort.direction == SortDirection::Desc {
-            ord.reverse()
-        } else {
-            ord
+    matches!(p.kind, ProcessKind::Kernel | ProcessKind::System)
+}
+
+/// Sort by `keys` in order; later keys break ties in earlier ones.
+pub fn apply_sort(processes: &mut [ProcessDto], keys: &[SortSpec]) {
+    processes.sort_by(|a, b| compare_processes(a, b, keys));
+}
+
+/// Ordering of two processes under `keys`, shared by list and tree sorting.
+/// Ties left by every key fall back to ascending PID so the order is stable
+/// across snapshots.
+pub fn compare_processes(a: &ProcessDto, b: &ProcessDto, keys: &[SortSpec]) -> Ordering {
+    keys.iter()
+        .map(|key| compare_by(a, b, key))
+        .find(|ord| ord.is_ne())
+        .unwrap_or_else(|| a.pid.cmp(&b.pid))
+}
+
+fn compare_by(a: &ProcessDto, b: &ProcessDto, sort: &SortSpec) -> Ordering {
+    let dir = &sort.direction;
+    match sort.field {
+        SortField::Pid => compare_optional(Some(a.pid), Some(b.pid), dir),
+        SortField::Name => compare_optional(
+            Some(a.name.to_lowercase()),
+            Some(b.name.to_lowercase()),
+            dir,
+        ),
+        SortField::CpuPercent => compare_optional(Some(a.cpu_percent), Some(b.cpu_percent), dir),
+        SortField::MemoryBytes => compare_optional(Some(a.memory_bytes), Some(b.memory_bytes), dir),
+        SortField::User => compare_optional(a.user.as_deref(), b.user.as_deref(), dir),
+        SortField::Status => {
+            compare_optional(Some(a.status.sort_rank()), Some(b.status.sort_rank()), dir)
+        }
+        SortField::StartTime => compare_optional(a.start_time, b.start_time, dir),
+        SortField::OomScore => compare_optional(a.oom_score, b.oom_score, dir),
+        SortField::ParentPid => compare_optional(a.parent_pid, b.parent_pid, dir),
+        SortField::Path => compare_optional(a.path.as_deref(), b.path.as_deref(), dir),
+        SortField::Cmd => compare_optional(command_line(a), command_line(b), dir),
+        SortField::Threads => compare_optional(a.threads, b.threads, dir),
+        SortField::Nice => compare_optional(a.nice, b.nice, dir),
+        SortField::DiskReadBytes => compare_optional(a.disk_read_bytes, b.disk_read_bytes, dir),
+        SortField::DiskWrittenBytes => {
+            compare_optional(a.disk_written_bytes, b.disk_written_bytes, dir)
+        }
+        SortField::Age => {
+            compare_optional(a.start_time.map(Reverse), b.start_time.map(Reverse), dir)
+        }
+    }
+}
+
+/// Compare two sort keys. A missing value ranks below every present one, as
+/// an empty user or zero start time did, so it comes first when ascending and
+/// last when descending.
+fn compare_optional<T: PartialOrd>(a: Option<T>, b: Option<T>, dir: &SortDirection) -> Ordering {
+    let ord = match (a, b) {
+        (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        (Some(_), None) => Ordering::Greater,
+        (None, Some(_)) => Ordering::Less,
+        (None, None) => Ordering::Equal,
+    };
+    if *dir == SortDirection::Desc {
+        ord.reverse()
+    } else {
+        ord
+    }
+}
+
+fn command_line(p: &ProcessDto) -> Option<String> {
+    (!p.cmd.is_empty()).then(|| p.cmd.join(" "))
+}
+
+#[cfg(test)]
+impl ProcessDto {
+    /// A running user process with every optional field empty; tests fill in
+    /// what they need with struct-update syntax.
+    pub fn test(pid: u32, name: &str) -> Self {
+        Self {
+            pid,
+            name: name.to_string(),
+            status: ProcessStatus::Running,
+            status_since_ms: None,
+            cpu_percent: 0.0,
+            memory_bytes: 0,
+            user: None,
+            path: None,
+            cwd: None,
+            parent_pid: None,
+            start_time: None,
+            needs_elevation: false,
+            kind: ProcessKind::User,
+            cmd: vec![],
+            threads: None,
+            disk_read_bytes: None,
+            disk_written_bytes: None,
+            nice: None,
+            scheduling_policy: None,
+            oom_score: None,
+            oom_score_adj: None,
+            launched_by_app: false,
         }
-    });
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -226,17 +720,11 @@ mod tests {
 
     fn make_proc(pid: u32, name: &str, cpu: f32, mem: u64, user: &str) -> ProcessDto {
         ProcessDto {
-            pid,
-            name: name.to_string(),
-            status: ProcessStatus::Running,
             cpu_percent: cpu,
             memory_bytes: mem,
             user: Some(user.to_string()),
             path: Some(format!("/usr/bin/{}", name)),
-            parent_pid: None,
-            start_time: None,
-            needs_elevation: false,
-            cmd: vec![],
+            ..ProcessDto::test(pid, name)
         }
     }
 
@@ -260,6 +748,74 @@ mod tests {
         assert!(f.matches(&p, "alice"));
     }
 
+    #[test]
+    fn test_filter_search_modes() {
+        let p = ProcessDto {
+            cmd: vec![
+                "python3".to_string(),
+                "manage.py".to_string(),
+                "runserver".to_string(),
+            ],
+            cwd: Some("/srv/site".to_string()),
+            ..make_proc(42, "python3", 1.0, 512, "alice")
+        };
+        let other = make_proc(43, "python3", 1.0, 512, "alice");
+        let search = |q: &str, mode| {
+            let mut f = ProcessFilter {
+                search: Some(q.to_string()),
+                search_mode: mode,
+                ..Default::default()
+            };
+            f.compile().unwrap();
+            (f.matches(&p, "alice"), f.matches(&other, "alice"))
+        };
+
+        assert_eq!(
+            search("Manage.py Runserver", SearchMode::Substring),
+            (true, false)
+        );
+        assert_eq!(search("/srv", SearchMode::Substring), (true, false));
+        assert_eq!(
+            search(r"manage\.py\s+run", SearchMode::Regex),
+            (true, false)
+        );
+        assert_eq!(search("python3 *.py run*", SearchMode::Glob), (true, false));
+        assert_eq!(search("[!p]*.py", SearchMode::Glob), (true, false));
+        assert_eq!(search("manage.py", SearchMode::Exact), (true, false));
+        assert_eq!(search("MANAGE.PY", SearchMode::Exact), (false, false));
+        assert_eq!(search("manage", SearchMode::Exact), (false, false));
+        assert_eq!(search("alice", SearchMode::Exact), (true, true));
+    }
+
+    #[test]
+    fn test_glob_leading_bracket_is_literal() {
+        assert_eq!(glob_to_regex("[]a]"), r"^[\]a]$");
+        assert_eq!(glob_to_regex("[!]]x"), r"^[^\]]x$");
+        let re = search_regex("x[]]", SearchMode::Glob).unwrap().unwrap();
+        assert!(re.is_match("x]"));
+        assert!(!re.is_match("xa"));
+    }
+
+    #[test]
+    fn test_filter_invalid_regex() {
+        let mut f = ProcessFilter {
+            search: Some("(unclosed".to_string()),
+            search_mode: SearchMode::Regex,
+            ..Default::default()
+        };
+        match f.compile() {
+            Err(AppError::InvalidRegex { pattern, .. }) => assert_eq!(pattern, "(unclosed"),
+            other => panic!("expected InvalidRegex, got {:?}", other),
+        }
+        // Uncompiled, a malformed pattern matches nothing.
+        assert!(!f.matches(&make_proc(1, "unclosed", 0.0, 0, "alice"), "alice"));
+
+        f.search_mode = SearchMode::Substring;
+        f.compile().unwrap();
+        assert!(f.search_regex.is_none());
+        assert!(f.matches(&make_proc(1, "(unclosed", 0.0, 0, "alice"), "alice"));
+    }
+
     #[test]
     fn test_filter_mine_only() {
         let p1 = make_proc(1, "init", 0.0, 100, "root");
@@ -284,6 +840,45 @@ mod tests {
         assert!(f.matches(&p2, "alice"));
     }
 
+    #[test]
+    fn test_filter_is_empty() {
+        assert!(ProcessFilter::default().is_empty());
+        let f = ProcessFilter {
+            search: Some(String::new()),
+            mine_only: Some(false),
+            ..Default::default()
+        };
+        assert!(f.is_empty());
+        let f = ProcessFilter {
+            user: Some("test".to_string()),
+            ..Default::default()
+        };
+        assert!(!f.is_empty());
+    }
+
+    #[test]
+    fn test_filter_query() {
+        let java = make_proc(10, "java", 35.0, 1024, "alice");
+        let bash = make_proc(11, "bash", 35.0, 1024, "alice");
+        let mut f = ProcessFilter {
+            query: Some("cpu > 20 and (name ~ /java/ or user = bob)".to_string()),
+            mine_only: Some(true),
+            ..Default::default()
+        };
+        f.compile().unwrap();
+        assert!(f.compiled.is_some());
+        assert!(!f.is_empty());
+        assert!(f.matches(&java, "alice"));
+        assert!(!f.matches(&bash, "alice"));
+        assert!(!f.matches(&java, "bob"));
+
+        f.query = Some("cpu >".to_string());
+        assert!(matches!(f.compile(), Err(AppError::InvalidFilter { .. })));
+        f.query = Some("  ".to_string());
+        f.compile().unwrap();
+        assert!(f.compiled.is_none());
+    }
+
     #[test]
     fn test_sort_by_cpu_desc() {
         let mut procs = vec![
@@ -293,10 +888,10 @@ mod tests {
         ];
         apply_sort(
             &mut procs,
-            &SortSpec {
+            &[SortSpec {
                 field: SortField::CpuPercent,
                 direction: SortDirection::Desc,
-            },
+            }],
         );
         assert_eq!(procs[0].pid, 2);
         assert_eq!(procs[1].pid, 3);
@@ -312,13 +907,95 @@ mod tests {
         ];
         apply_sort(
             &mut procs,
-            &SortSpec {
+            &[SortSpec {
                 field: SortField::Name,
                 direction: SortDirection::Asc,
-            },
+            }],
         );
         assert_eq!(procs[0].name, "ash");
         assert_eq!(procs[1].name, "bash");
         assert_eq!(procs[2].name, "zsh");
     }
+
+    #[test]
+    fn test_sort_by_oom_score_desc() {
+        let mut procs: Vec<ProcessDto> = [(1, Some(10)), (2, None), (3, Some(900))]
+            .into_iter()
+            .map(|(pid, score)| ProcessDto {
+                oom_score: score,
+                ..make_proc(pid, "p", 0.0, 0, "u")
+            })
+            .collect();
+        apply_sort(
+            &mut procs,
+            &[SortSpec {
+                field: SortField::OomScore,
+                direction: SortDirection::Desc,
+            }],
+        );
+        let pids: Vec<u32> = procs.iter().map(|p| p.pid).collect();
+        assert_eq!(pids, vec![3, 1, 2]);
+    }
+
+    #[test]
+    fn test_sort_multiple_keys() {
+        let mut procs = vec![
+            make_proc(5, "a", 0.0, 100, "bob"),
+            make_proc(4, "b", 0.0, 300, "alice"),
+            make_proc(3, "c", 0.0, 100, "alice"),
+            make_proc(2, "d", 0.0, 300, "bob"),
+            make_proc(1, "e", 0.0, 100, "alice"),
+        ];
+        apply_sort(
+            &mut procs,
+            &[
+                SortSpec {
+                    field: SortField::User,
+                    direction: SortDirection::Asc,
+                },
+                SortSpec {
+                    field: SortField::MemoryBytes,
+                    direction: SortDirection::Desc,
+                },
+            ],
+        );
+        // Ties on both keys fall back to ascending PID.
+        let pids: Vec<u32> = procs.iter().map(|p| p.pid).collect();
+        assert_eq!(pids, vec![4, 1, 3, 2, 5]);
+    }
+
+    #[test]
+    fn test_sort_status_rank_and_missing_values() {
+        let mut procs: Vec<ProcessDto> = [
+            (1, ProcessStatus::Zombie, Some(30)),
+            (2, ProcessStatus::Sleeping, None),
+            (3, ProcessStatus::Running, Some(10)),
+        ]
+        .into_iter()
+        .map(|(pid, status, start_time)| ProcessDto {
+            status,
+            start_time,
+            ..make_proc(pid, "p", 0.0, 0, "u")
+        })
+        .collect();
+
+        let by = |field| {
+            [SortSpec {
+                field,
+                direction: SortDirection::Asc,
+            }]
+        };
+        apply_sort(&mut procs, &by(SortField::Status));
+        let pids: Vec<u32> = procs.iter().map(|p| p.pid).collect();
+        assert_eq!(pids, vec![3, 2, 1]);
+
+        // The process without a start time ranks lowest, so it comes first
+        // when ascending (and last when descending, see the OOM score test).
+        apply_sort(&mut procs, &by(SortField::StartTime));
+        let pids: Vec<u32> = procs.iter().map(|p| p.pid).collect();
+        assert_eq!(pids, vec![2, 3, 1]);
+        apply_sort(&mut procs, &by(SortField::Age));
+        let pids: Vec<u32> = procs.iter().map(|p| p.pid).collect();
+        assert_eq!(pids, vec![2, 1, 3]);
+    }
 }
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+/// Criterion that marks a process as protected from kill-type actions.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum ProtectionRule {
+    Pid(u32),
+    /// Process name, compared case-insensitively
+    Name(String),
+    /// Full executable path
+    Path(String),
+    /// Owning username
+    User(String),
+}
+
+/// A rule as listed to the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProtectionEntry {
+    pub rule: ProtectionRule,
+    /// Built-in defaults cannot be removed, only overridden per action
+    pub builtin: bool,
+    /// Why the rule exists, shown in `AppError::Protected`
+    pub reason: String,
+    /// Start time (Unix seconds) of the process a built-in PID rule was made
+    /// for, so a later process reusing the PID is not protected by it
+    pub start_time: Option<u64>,
+}
@@ -0,0 +1,802 @@
+use std::{
+    fmt,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use regex::{Regex, RegexBuilder};
+
+use super::process::{ProcessDto, ProcessStatus};
+
+/// A parsed filter expression such as
+/// `cpu > 20 and user = alice and (name ~ /java/ or cmd contains "--port")`.
+///
+/// Grammar, loosest binding first:
+///
+/// ```text
+/// expr       = and ("or" | "||") and ...
+/// and        = not ("and" | "&&") not ...
+/// not        = ("not" | "!") not | "(" expr ")" | comparison
+/// comparison = field op value
+/// ```
+///
+/// Text fields (`name`, `path`, `cmd`, `cwd`, `user`) take `=`, `!=` and
+/// `contains`, compared case-insensitively, plus `~` / `!~` with a `/regex/`
+/// (`/regex/i` ignores case). Numeric fields take `=`, `!=`, `>`, `>=`, `<`,
+/// `<=` and `in lo..hi` (inclusive); `mem` accepts `K`, `M`, `G` and `T`
+/// suffixes. `status` takes `=` and `!=`; `status_secs` is the time spent in
+/// it. A comparison against a value the process does not report (e.g. `nice`
+/// on Windows) is false.
+#[derive(Debug, Clone)]
+pub struct Query {
+    expr: Expr,
+}
+
+/// Parse failure with the 1-based column it was detected at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError {
+    pub message: String,
+    pub column: usize,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (column {})", self.message, self.column)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+impl Query {
+    pub fn parse(input: &str) -> Result<Self, QueryError> {
+        let mut parser = Parser {
+            input,
+            pos: 0,
+            depth: 0,
+        };
+        parser.skip_ws();
+        if parser.at_end() {
+            return Err(parser.error(0, "query is empty".to_string()));
+        }
+        let expr = parser.parse_or()?;
+        parser.skip_ws();
+        if !parser.at_end() {
+            let found = parser.describe_next();
+            return Err(parser.error(
+                parser.pos,
+                format!("expected 'and', 'or' or the end of the query, found {found}"),
+            ));
+        }
+        Ok(Self { expr })
+    }
+
+    pub fn matches(&self, p: &ProcessDto) -> bool {
+        self.expr.eval(p)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    /// Chains are kept flat so long ones don't deepen the tree.
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>),
+    Compare(Field, Test),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Pid,
+    Ppid,
+    Name,
+    Path,
+    Cmd,
+    Cwd,
+    User,
+    Status,
+    Cpu,
+    Memory,
+    Threads,
+    Nice,
+    OomScore,
+    StartTime,
+    StatusSecs,
+}
+
+/// Field names accepted in queries, aliases after the canonical name.
+const FIELDS: &[(&str, Field)] = &[
+    ("pid", Field::Pid),
+    ("ppid", Field::Ppid),
+    ("name", Field::Name),
+    ("path", Field::Path),
+    ("exe", Field::Path),
+    ("cmd", Field::Cmd),
+    ("cwd", Field::Cwd),
+    ("user", Field::User),
+    ("status", Field::Status),
+    ("cpu", Field::Cpu),
+    ("mem", Field::Memory),
+    ("memory", Field::Memory),
+    ("threads", Field::Threads),
+    ("nice", Field::Nice),
+    ("oom_score", Field::OomScore),
+    ("start_time", Field::StartTime),
+    ("status_secs", Field::StatusSecs),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Match,
+    NotMatch,
+    Contains,
+    In,
+}
+
+/// Operators in the order they are tried, so `>=` wins over `>`.
+const SYMBOL_OPS: &[(&str, Op)] = &[
+    (">=", Op::Ge),
+    ("<=", Op::Le),
+    ("==", Op::Eq),
+    ("!=", Op::Ne),
+    ("!~", Op::NotMatch),
+    ("=", Op::Eq),
+    (">", Op::Gt),
+    ("<", Op::Lt),
+    ("~", Op::Match),
+];
+
+#[derive(Debug, Clone)]
+enum Test {
+    Number(Op, f64),
+    Range(f64, f64),
+    Text(Op, String),
+    Regex(Regex, bool),
+    Status(ProcessStatus, bool),
+}
+
+impl Field {
+    fn is_text(self) -> bool {
+        matches!(
+            self,
+            Field::Name | Field::Path | Field::Cmd | Field::Cwd | Field::User
+        )
+    }
+
+    fn number(self, p: &ProcessDto) -> Option<f64> {
+        match self {
+            Field::Pid => Some(f64::from(p.pid)),
+            Field::Ppid => p.parent_pid.map(f64::from),
+            Field::Cpu => Some(f64::from(p.cpu_percent)),
+            Field::Memory => Some(p.memory_bytes as f64),
+            Field::Threads => p.threads.map(f64::from),
+            Field::Nice => p.nice.map(f64::from),
+            Field::OomScore => p.oom_score.map(f64::from),
+            Field::StartTime => p.start_time.map(|t| t as f64),
+            Field::StatusSecs => p.status_since_ms.map(|since| {
+                let now_ms = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_millis() as u64)
+                    .unwrap_or(0);
+                now_ms.saturating_sub(since) as f64 / 1000.0
+            }),
+            _ => None,
+        }
+    }
+
+    fn text(self, p: &ProcessDto) -> Option<String> {
+        match self {
+            Field::Name => Some(p.name.clone()),
+            Field::Path => p.path.clone(),
+            Field::Cmd => (!p.cmd.is_empty()).then(|| p.cmd.join(" ")),
+            Field::Cwd => p.cwd.clone(),
+            Field::User => p.user.clone(),
+            _ => None,
+        }
+    }
+}
+
+impl Expr {
+    fn eval(&self, p: &ProcessDto) -> bool {
+        match self {
+            Expr::And(terms) => terms.iter().all(|e| e.eval(p)),
+            Expr::Or(terms) => terms.iter().any(|e| e.eval(p)),
+            Expr::Not(e) => !e.eval(p),
+            Expr::Compare(field, test) => compare(*field, test, p),
+        }
+    }
+}
+
+fn compare(field: Field, test: &Test, p: &ProcessDto) -> bool {
+    match test {
+        Test::Status(status, negate) => (&p.status == status) != *negate,
+        Test::Number(op, value) => field.number(p).is_some_and(|n| match op {
+            Op::Eq => n == *value,
+            Op::Ne => n != *value,
+            Op::Gt => n > *value,
+            Op::Ge => n >= *value,
+            Op::Lt => n < *value,
+            Op::Le => n <= *value,
+            _ => false,
+        }),
+        Test::Range(lo, hi) => field.number(p).is_some_and(|n| *lo <= n && n <= *hi),
+        Test::Text(op, value) => field.text(p).is_some_and(|text| match op {
+            Op::Eq => text.to_lowercase() == *value,
+            Op::Ne => text.to_lowercase() != *value,
+            Op::Contains => text.to_lowercase().contains(value.as_str()),
+            _ => false,
+        }),
+        Test::Regex(re, negate) => field
+            .text(p)
+            .is_some_and(|text| re.is_match(&text) != *negate),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Parser
+// ---------------------------------------------------------------------------
+
+/// Deepest nesting of `not` and parentheses a query may use.
+const MAX_DEPTH: usize = 64;
+
+struct Parser<'a> {
+    input: &'a str,
+    /// Byte offset of the next unread character
+    pos: usize,
+    /// Current nesting of `not` and parentheses
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn parse_or(&mut self) -> Result<Expr, QueryError> {
+        let mut terms = vec![self.parse_and()?];
+        while self.eat_keyword("or") || self.eat_symbol("||") {
+            terms.push(self.parse_and()?);
+        }
+        Ok(match terms.len() {
+            1 => terms.remove(0),
+            _ => Expr::Or(terms),
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, QueryError> {
+        let mut terms = vec![self.parse_not()?];
+        while self.eat_keyword("and") || self.eat_symbol("&&") {
+            terms.push(self.parse_not()?);
+        }
+        Ok(match terms.len() {
+            1 => terms.remove(0),
+            _ => Expr::And(terms),
+        })
+    }
+
+    fn parse_not(&mut self) -> Result<Expr, QueryError> {
+        self.skip_ws();
+        let open = self.pos;
+        if self.eat_keyword("not") || self.eat_symbol("!") {
+            let inner = self.nested(open, Self::parse_not)?;
+            return Ok(Expr::Not(Box::new(inner)));
+        }
+        if self.eat_symbol("(") {
+            let inner = self.nested(open, Self::parse_or)?;
+            if !self.eat_symbol(")") {
+                let found = self.describe_next();
+                return Err(self.error(
+                    self.pos,
+                    format!(
+                        "expected ')' to close the '(' at column {}, found {found}",
+                        self.column(open)
+                    ),
+                ));
+            }
+            return Ok(inner);
+        }
+        self.parse_comparison()
+    }
+
+    /// Run `parse` one nesting level deeper, failing past `MAX_DEPTH` rather
+    /// than overflowing the stack.
+    fn nested(
+        &mut self,
+        at: usize,
+        parse: fn(&mut Self) -> Result<Expr, QueryError>,
+    ) -> Result<Expr, QueryError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(
+                at,
+                format!("query is nested more than {MAX_DEPTH} levels deep"),
+            ));
+        }
+        self.depth += 1;
+        let expr = parse(self);
+        self.depth -= 1;
+        expr
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, QueryError> {
+        self.skip_ws();
+        let start = self.pos;
+        let Some(name) = self.word() else {
+            let found = self.describe_next();
+            return Err(self.error(start, format!("expected a field name, found {found}")));
+        };
+        let field = FIELDS
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, f)| *f)
+            .ok_or_else(|| {
+                let known: Vec<&str> = FIELDS.iter().map(|(n, _)| *n).collect();
+                self.error(
+                    start,
+                    format!(
+                        "unknown field '{name}'; expected one of {}",
+                        known.join(", ")
+                    ),
+                )
+            })?;
+
+        self.skip_ws();
+        let op_pos = self.pos;
+        let op = self.parse_op().ok_or_else(|| {
+            let found = self.describe_next();
+            self.error(
+                op_pos,
+                format!(
+                    "expected an operator after '{name}' \
+                     (=, !=, >, >=, <, <=, ~, !~, contains, in), found {found}"
+                ),
+            )
+        })?;
+
+        let test = match field {
+            Field::Status => self.parse_status(name, op, op_pos)?,
+            f if f.is_text() => self.parse_text(name, op, op_pos)?,
+            _ => self.parse_number_test(name, field, op, op_pos)?,
+        };
+        Ok(Expr::Compare(field, test))
+    }
+
+    fn parse_op(&mut self) -> Option<Op> {
+        for (symbol, op) in SYMBOL_OPS {
+            if self.eat_symbol(symbol) {
+                return Some(*op);
+            }
+        }
+        if self.eat_keyword("contains") {
+            return Some(Op::Contains);
+        }
+        if self.eat_keyword("in") {
+            return Some(Op::In);
+        }
+        None
+    }
+
+    fn parse_status(&mut self, name: &str, op: Op, op_pos: usize) -> Result<Test, QueryError> {
+        let negate = match op {
+            Op::Eq => false,
+            Op::Ne => true,
+            _ => {
+                return Err(self.error(op_pos, format!("'{name}' only supports = and !=")));
+            }
+        };
+        let (at, value) = self.parse_value(name)?;
+        let status = serde_json::from_value(serde_json::Value::String(value.to_lowercase()))
+            .map_err(|_| {
+                self.error(
+                    at,
+                    format!(
+                        "unknown status '{value}'; expected running, sleeping, idle, disk_sleep, \
+                         stopped, tracing_stop, zombie, dead, wakekill, parked, lock_blocked \
+                         or unknown"
+                    ),
+                )
+            })?;
+        Ok(Test::Status(status, negate))
+    }
+
+    fn parse_text(&mut self, name: &str, op: Op, op_pos: usize) -> Result<Test, QueryError> {
+        match op {
+            Op::Eq | Op::Ne | Op::Contains => {
+                let (_, value) = self.parse_value(name)?;
+                Ok(Test::Text(op, value.to_lowercase()))
+            }
+            Op::Match | Op::NotMatch => {
+                let re = self.parse_regex(name)?;
+                Ok(Test::Regex(re, op == Op::NotMatch))
+            }
+            _ => Err(self.error(
+                op_pos,
+                format!("'{name}' is text; use =, !=, contains, ~ or !~"),
+            )),
+        }
+    }
+
+    fn parse_number_test(
+        &mut self,
+        name: &str,
+        field: Field,
+        op: Op,
+        op_pos: usize,
+    ) -> Result<Test, QueryError> {
+        match op {
+            Op::Eq | Op::Ne | Op::Gt | Op::Ge | Op::Lt | Op::Le => {
+                let (at, value) = self.parse_value(name)?;
+                Ok(Test::Number(op, self.number(name, field, &value, at)?))
+            }
+            Op::In => {
+                let (at, value) = self.parse_value(name)?;
+                let (lo, hi) = value.split_once("..").ok_or_else(|| {
+                    self.error(at, format!("expected a range like 10..20, found '{value}'"))
+                })?;
+                let lo = self.number(name, field, lo, at)?;
+                let hi = self.number(name, field, hi, at)?;
+                if lo > hi {
+                    return Err(self.error(at, format!("range {value} is empty")));
+                }
+                Ok(Test::Range(lo, hi))
+            }
+            _ => Err(self.error(
+                op_pos,
+                format!("'{name}' is numeric; use =, !=, >, >=, <, <= or in"),
+            )),
+        }
+    }
+
+    /// A quoted string or a bare word, with the offset it started at.
+    fn parse_value(&mut self, name: &str) -> Result<(usize, String), QueryError> {
+        self.skip_ws();
+        let start = self.pos;
+        match self.peek() {
+            Some(quote @ ('"' | '\'')) => {
+                self.pos += 1;
+                let mut value = String::new();
+                let mut chars = self.input[self.pos..].char_indices();
+                while let Some((i, c)) = chars.next() {
+                    match c {
+                        '\\' => {
+                            if let Some((_, escaped)) = chars.next() {
+                                value.push(escaped);
+                            }
+                        }
+                        c if c == quote => {
+                            self.pos += i + 1;
+                            return Ok((start, value));
+                        }
+                        c => value.push(c),
+                    }
+                }
+                Err(self.error(start, "unterminated string".to_string()))
+            }
+            _ => match self.word() {
+                Some(word) => Ok((start, word.to_string())),
+                None => {
+                    let found = self.describe_next();
+                    Err(self.error(
+                        start,
+                        format!("expected a value for '{name}', found {found}"),
+                    ))
+                }
+            },
+        }
+    }
+
+    /// `/pattern/` with an optional `i` flag, or a plain value used as the pattern.
+    fn parse_regex(&mut self, name: &str) -> Result<Regex, QueryError> {
+        self.skip_ws();
+        let start = self.pos;
+        let (pattern, ignore_case) = if self.peek() == Some('/') {
+            self.pos += 1;
+            let rest = &self.input[self.pos..];
+            let mut end = None;
+            let mut escaped = false;
+            for (i, c) in rest.char_indices() {
+                match c {
+                    '\\' if !escaped => escaped = true,
+                    '/' if !escaped => {
+                        end = Some(i);
+                        break;
+                    }
+                    _ => escaped = false,
+                }
+            }
+            let end = end.ok_or_else(|| self.error(start, "unterminated regex".to_string()))?;
+            // An escaped delimiter means a literal slash.
+            let pattern = rest[..end].replace("\\/", "/");
+            self.pos += end + 1;
+            let ignore_case = self.input[self.pos..].starts_with('i');
+            if ignore_case {
+                self.pos += 1;
+            }
+            (pattern, ignore_case)
+        } else {
+            (self.parse_value(name)?.1, false)
+        };
+
+        RegexBuilder::new(&pattern)
+            .case_insensitive(ignore_case)
+            .build()
+            .map_err(|e| self.error(start, format!("invalid regex: {e}")))
+    }
+
+    fn number(&self, name: &str, field: Field, text: &str, at: usize) -> Result<f64, QueryError> {
+        let invalid = || {
+            self.error(
+                at,
+                format!("expected a number for '{name}', found '{text}'"),
+            )
+        };
+        let text = text.trim();
+        let (digits, scale) = match field {
+            Field::Memory => split_size_suffix(text).ok_or_else(invalid)?,
+            Field::Cpu => (text.strip_suffix('%').unwrap_or(text), 1.0),
+            _ => (text, 1.0),
+        };
+        digits
+            .parse::<f64>()
+            .ok()
+            .filter(|n| n.is_finite())
+            .map(|n| n * scale)
+            .ok_or_else(invalid)
+    }
+
+    fn skip_ws(&mut self) {
+        let rest = &self.input[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.input.len()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        self.skip_ws();
+        let rest = &self.input[self.pos..];
+        // `!` alone must not swallow the start of `!=` or `!~`.
+        if symbol == "!" && (rest.starts_with("!=") || rest.starts_with("!~")) {
+            return false;
+        }
+        if rest.starts_with(symbol) {
+            self.pos += symbol.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consume `keyword` (any case) if it is the next whole word.
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        self.skip_ws();
+        let start = self.pos;
+        match self.word() {
+            Some(word) if word.eq_ignore_ascii_case(keyword) => true,
+            _ => {
+                self.pos = start;
+                false
+            }
+        }
+    }
+
+    /// Next run of characters that are not whitespace or syntax.
+    fn word(&mut self) -> Option<&'a str> {
+        let rest = &self.input[self.pos..];
+        let len = rest
+            .find(|c: char| c.is_whitespace() || "()!=<>~\"'&|".contains(c))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return None;
+        }
+        self.pos += len;
+        Some(&rest[..len])
+    }
+
+    fn describe_next(&self) -> String {
+        let rest = &self.input[self.pos..];
+        match rest.chars().next() {
+            None => "end of query".to_string(),
+            Some(c) if "()!=<>~\"'&|".contains(c) => format!("'{c}'"),
+            Some(_) => {
+                let len = rest
+                    .find(|c: char| c.is_whitespace() || "()!=<>~\"'&|".contains(c))
+                    .unwrap_or(rest.len());
+                format!("'{}'", &rest[..len])
+            }
+        }
+    }
+
+    fn column(&self, offset: usize) -> usize {
+        self.input[..offset].chars().count() + 1
+    }
+
+    fn error(&self, offset: usize, message: String) -> QueryError {
+        QueryError {
+            message,
+            column: self.column(offset),
+        }
+    }
+}
+
+/// Split `512M`, `1.5GiB` or `2048` into the number and its byte multiplier.
+fn split_size_suffix(text: &str) -> Option<(&str, f64)> {
+    let lower = text.to_ascii_lowercase();
+    let unit_start = lower
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(lower.len());
+    let scale = match &lower[unit_start..] {
+        "" | "b" => 1.0,
+        "k" | "kb" | "kib" => 1024.0,
+        "m" | "mb" | "mib" => 1024.0 * 1024.0,
+        "g" | "gb" | "gib" => 1024.0 * 1024.0 * 1024.0,
+        "t" | "tb" | "tib" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((&text[..unit_start], scale))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_proc(pid: u32, name: &str, cpu: f32, mem: u64, user: &str, cmd: &[&str]) -> ProcessDto {
+        ProcessDto {
+            cpu_percent: cpu,
+            memory_bytes: mem,
+            user: Some(user.to_string()),
+            path: Some(format!("/usr/bin/{}", name)),
+            parent_pid: Some(1),
+            cmd: cmd.iter().map(|s| s.to_string()).collect(),
+            nice: Some(0),
+            ..ProcessDto::test(pid, name)
+        }
+    }
+
+    fn matches(query: &str, p: &ProcessDto) -> bool {
+        Query::parse(query).unwrap().matches(p)
+    }
+
+    #[test]
+    fn test_example_query() {
+        let query = r#"cpu > 20 and user = alice and (name ~ /java/ or cmd contains "--port")"#;
+        let java = make_proc(10, "java", 35.0, 0, "alice", &["java", "-jar", "app.jar"]);
+        let node = make_proc(
+            11,
+            "node",
+            50.0,
+            0,
+            "alice",
+            &["node", "server.js", "--port=80"],
+        );
+        let idle = make_proc(12, "java", 1.0, 0, "alice", &["java"]);
+        let bob = make_proc(13, "java", 90.0, 0, "bob", &["java"]);
+
+        assert!(matches(query, &java));
+        assert!(matches(query, &node));
+        assert!(!matches(query, &idle));
+        assert!(!matches(query, &bob));
+    }
+
+    #[test]
+    fn test_precedence_and_negation() {
+        let p = make_proc(10, "bash", 0.0, 0, "alice", &[]);
+        // `and` binds tighter than `or`.
+        assert!(matches("name = zsh and pid = 1 or pid = 10", &p));
+        assert!(!matches("name = zsh and (pid = 1 or pid = 10)", &p));
+        assert!(matches("not name = zsh", &p));
+        assert!(matches("!(user = root) && pid in 5..10", &p));
+        assert!(!matches("name !~ /^ba/", &p));
+        assert!(matches("name ~ /BASH/i", &p));
+        assert!(matches("NAME = BASH", &p));
+    }
+
+    #[test]
+    fn test_numeric_fields() {
+        let p = make_proc(10, "db", 12.5, 3 * 1024 * 1024 * 1024, "pg", &[]);
+        assert!(matches("mem > 2G and mem <= 3GiB", &p));
+        assert!(matches("cpu >= 12.5% and cpu < 13", &p));
+        assert!(matches("ppid = 1 and nice = 0", &p));
+        assert!(matches("status = running and status != zombie", &p));
+        assert!(!matches("status_secs > 0", &p));
+        let stuck = ProcessDto {
+            status: ProcessStatus::DiskSleep,
+            status_since_ms: Some(1_000),
+            ..p.clone()
+        };
+        assert!(matches("status = disk_sleep and status_secs > 300", &stuck));
+        // Values the process does not report never match.
+        assert!(!matches("threads > 0", &p));
+        assert!(!matches("threads <= 0", &p));
+        assert!(!matches("cmd contains x", &p));
+    }
+
+    #[test]
+    fn test_quoted_values_and_paths() {
+        let p = make_proc(
+            10,
+            "my app",
+            0.0,
+            0,
+            "alice",
+            &["/opt/my app/bin", "--name=\"x\""],
+        );
+        assert!(matches(r#"name = "my app""#, &p));
+        assert!(matches("path = '/usr/bin/my app'", &p));
+        assert!(matches(r#"cmd contains "name=\"x\"""#, &p));
+        assert!(matches(r"cmd ~ /^\/opt\//", &p));
+    }
+
+    fn error(query: &str) -> QueryError {
+        Query::parse(query).unwrap_err()
+    }
+
+    #[test]
+    fn test_error_messages() {
+        let e = error("cpu > 20 and");
+        assert_eq!(e.message, "expected a field name, found end of query");
+        assert_eq!(e.column, 13);
+
+        let e = error("cpuu > 20");
+        assert!(e
+            .message
+            .starts_with("unknown field 'cpuu'; expected one of pid, ppid"));
+        assert_eq!(e.column, 1);
+
+        let e = error("(name = a or name = b");
+        assert_eq!(
+            e.message,
+            "expected ')' to close the '(' at column 1, found end of query"
+        );
+
+        assert_eq!(
+            error("name > 5").message,
+            "'name' is text; use =, !=, contains, ~ or !~"
+        );
+        assert_eq!(
+            error("cpu ~ /x/").message,
+            "'cpu' is numeric; use =, !=, >, >=, <, <= or in"
+        );
+        assert_eq!(
+            error("mem > 5X").message,
+            "expected a number for 'mem', found '5X'"
+        );
+        assert_eq!(error("pid in 10..5").message, "range 10..5 is empty");
+        assert!(error("name ~ /(/").message.starts_with("invalid regex"));
+        assert_eq!(error("name ~ /abc").message, "unterminated regex");
+        assert_eq!(error("status = asleep").column, 10);
+
+        let e = error("pid = 1 pid = 2");
+        assert_eq!(
+            e.message,
+            "expected 'and', 'or' or the end of the query, found 'pid'"
+        );
+        assert_eq!(e.column, 9);
+        assert_eq!(error("   ").message, "query is empty");
+    }
+
+    #[test]
+    fn test_nesting_limit() {
+        let p = make_proc(1, "init", 0.0, 0, "root", &[]);
+        let nested = |depth: usize| format!("{}pid = 1{}", "(".repeat(depth), ")".repeat(depth));
+        assert!(matches(&nested(MAX_DEPTH), &p));
+
+        let e = error(&nested(MAX_DEPTH + 1));
+        assert_eq!(e.message, "query is nested more than 64 levels deep");
+        assert_eq!(e.column, MAX_DEPTH + 1);
+        let e = error(&format!("{}pid = 1", "not ".repeat(100_000)));
+        assert_eq!(e.column, 4 * MAX_DEPTH + 1);
+
+        // Long flat chains are not nesting.
+        let chain = vec!["pid = 2"; 100_000].join(" or ");
+        assert!(matches(&format!("{chain} or pid = 1"), &p));
+    }
+}
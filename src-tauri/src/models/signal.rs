@@ -0,0 +1,162 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+/// Signal chosen in the frontend, by name (`"SIGHUP"`, `"hup"`) or by number.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum SignalSpec {
+    Number(i32),
+    Name(String),
+}
+
+impl fmt::Display for SignalSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Number(n) => write!(f, "{n}"),
+            Self::Name(name) => f.write_str(name),
+        }
+    }
+}
+
+/// POSIX signals accepted by name (without the `SIG` prefix).
+#[cfg(unix)]
+const SIGNALS: &[(&str, i32)] = &[
+    ("HUP", libc::SIGHUP),
+    ("INT", libc::SIGINT),
+    ("QUIT", libc::SIGQUIT),
+    ("ILL", libc::SIGILL),
+    ("TRAP", libc::SIGTRAP),
+    ("ABRT", libc::SIGABRT),
+    ("BUS", libc::SIGBUS),
+    ("FPE", libc::SIGFPE),
+    ("KILL", libc::SIGKILL),
+    ("USR1", libc::SIGUSR1),
+    ("SEGV", libc::SIGSEGV),
+    ("USR2", libc::SIGUSR2),
+    ("PIPE", libc::SIGPIPE),
+    ("ALRM", libc::SIGALRM),
+    ("TERM", libc::SIGTERM),
+    ("CHLD", libc::SIGCHLD),
+    ("CONT", libc::SIGCONT),
+    ("STOP", libc::SIGSTOP),
+    ("TSTP", libc::SIGTSTP),
+    ("TTIN", libc::SIGTTIN),
+    ("TTOU", libc::SIGTTOU),
+    ("URG", libc::SIGURG),
+    ("XCPU", libc::SIGXCPU),
+    ("XFSZ", libc::SIGXFSZ),
+    ("VTALRM", libc::SIGVTALRM),
+    ("PROF", libc::SIGPROF),
+    ("WINCH", libc::SIGWINCH),
+    ("IO", libc::SIGIO),
+    ("SYS", libc::SIGSYS),
+];
+
+#[cfg(unix)]
+impl SignalSpec {
+    /// Resolve to a platform signal number.
+    pub fn resolve(&self) -> Result<i32, AppError> {
+        let invalid = || AppError::InvalidSignal {
+            signal: self.to_string(),
+        };
+
+        match self {
+            Self::Number(n) if is_valid_number(*n) => Ok(*n),
+            Self::Number(_) => Err(invalid()),
+            Self::Name(name) => {
+                let trimmed = name.trim();
+                if let Ok(n) = trimmed.parse::<i32>() {
+                    return Self::Number(n).resolve().map_err(|_| invalid());
+                }
+                let upper = trimmed.to_ascii_uppercase();
+                let bare = upper.strip_prefix("SIG").unwrap_or(&upper);
+                SIGNALS
+                    .iter()
+                    .find(|(n, _)| *n == bare)
+                    .map(|(_, sig)| *sig)
+                    .ok_or_else(invalid)
+            }
+        }
+    }
+}
+
+/// `SIG`-prefixed name for a signal number, if it is one we know.
+#[cfg(unix)]
+pub fn signal_name(sig: i32) -> Option<String> {
+    SIGNALS
+        .iter()
+        .find(|(_, n)| *n == sig)
+        .map(|(name, _)| format!("SIG{}", name))
+}
+
+#[cfg(unix)]
+fn is_valid_number(n: i32) -> bool {
+    if SIGNALS.iter().any(|(_, sig)| *sig == n) {
+        return true;
+    }
+    #[cfg(target_os = "linux")]
+    {
+        (libc::SIGRTMIN()..=libc::SIGRTMAX()).contains(&n)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        false
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_by_name() {
+        for name in ["SIGHUP", "HUP", "hup", " SigHup "] {
+            assert_eq!(
+                SignalSpec::Name(name.to_string()).resolve().unwrap(),
+                libc::SIGHUP
+            );
+        }
+        assert_eq!(
+            SignalSpec::Name("stop".to_string()).resolve().unwrap(),
+            libc::SIGSTOP
+        );
+    }
+
+    #[test]
+    fn test_resolve_by_number() {
+        assert_eq!(
+            SignalSpec::Number(libc::SIGUSR1).resolve().unwrap(),
+            libc::SIGUSR1
+        );
+        assert_eq!(
+            SignalSpec::Name("9".to_string()).resolve().unwrap(),
+            libc::SIGKILL
+        );
+    }
+
+    #[test]
+    fn test_resolve_rejects_unknown() {
+        assert!(matches!(
+            SignalSpec::Name("SIGNOPE".to_string()).resolve(),
+            Err(AppError::InvalidSignal { signal }) if signal == "SIGNOPE"
+        ));
+        assert!(SignalSpec::Number(0).resolve().is_err());
+        assert!(SignalSpec::Number(-3).resolve().is_err());
+        assert!(SignalSpec::Number(1000).resolve().is_err());
+    }
+
+    #[test]
+    fn test_signal_spec_deserializes_name_or_number() {
+        let n: SignalSpec = serde_json::from_str("15").unwrap();
+        let s: SignalSpec = serde_json::from_str("\"SIGTERM\"").unwrap();
+        assert_eq!(n.resolve().unwrap(), s.resolve().unwrap());
+        assert_eq!(signal_name(libc::SIGTERM).as_deref(), Some("SIGTERM"));
+    }
+}
@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+/// System-wide metrics emitted alongside the per-process diff.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SystemSnapshot {
+    pub timestamp_ms: u64,
+    /// Overall CPU usage across all cores (0.0–100.0)
+    pub cpu_percent: f32,
+    /// Usage of each logical core (0.0–100.0)
+    pub per_core_cpu_percent: Vec<f32>,
+    pub memory_total_bytes: u64,
+    pub memory_used_bytes: u64,
+    pub memory_available_bytes: u64,
+    /// Page cache size (None where the OS does not report it)
+    pub memory_cached_bytes: Option<u64>,
+    pub swap_total_bytes: u64,
+    pub swap_used_bytes: u64,
+    pub load_average: LoadAverage,
+    pub uptime_secs: u64,
+    pub process_count: usize,
+    /// Sum of thread counts over all processes (None if no collector reports threads)
+    pub thread_count: Option<u64>,
+    /// Pressure stall information (Linux 4.20+ only)
+    pub pressure: Option<SystemPressure>,
+}
+
+/// 1, 5 and 15 minute load averages.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct LoadAverage {
+    pub one: f64,
+    pub five: f64,
+    pub fifteen: f64,
+}
+
+/// Contents of `/proc/pressure/{cpu,memory,io}`; a resource is None if its file is unreadable.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SystemPressure {
+    pub cpu: Option<PressureStall>,
+    pub memory: Option<PressureStall>,
+    pub io: Option<PressureStall>,
+}
+
+/// The `some` and `full` lines of one PSI file.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct PressureStall {
+    pub some: PressureAverages,
+    /// Missing for CPU on kernels older than 5.13
+    pub full: Option<PressureAverages>,
+}
+
+/// Share of time (percent) stalled over 10/60/300 s windows, plus the total stall time.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct PressureAverages {
+    pub avg10: f32,
+    pub avg60: f32,
+    pub avg300: f32,
+    pub total_us: u64,
+}
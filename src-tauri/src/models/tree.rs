@@ -0,0 +1,313 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::process::{compare_processes, ProcessDto, ProcessFilter, SortSpec, TreeKillOrder};
+
+/// One node of the process hierarchy returned by `process_tree`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessTreeNode {
+    pub process: ProcessDto,
+    pub children: Vec<ProcessTreeNode>,
+    /// CPU of this process plus every descendant in the tree
+    pub subtree_cpu_percent: f32,
+    /// Resident memory of this process plus every descendant in the tree
+    pub subtree_memory_bytes: u64,
+    /// Number of descendants in the tree (children, grandchildren, ...)
+    pub descendant_count: usize,
+}
+
+/// Build a forest from a flat snapshot.
+///
+/// Only processes matching `filter` appear. A process whose parent is hidden
+/// by the filter is attached to its nearest visible ancestor; one with no
+/// visible ancestor (orphans included) becomes a root. Siblings are ordered by
+/// `sort`. Aggregates cover the nodes present in the returned tree.
+pub fn build_process_tree<'a>(
+    processes: impl IntoIterator<Item = &'a ProcessDto>,
+    filter: &ProcessFilter,
+    current_user: &str,
+    sort: &[SortSpec],
+) -> Vec<ProcessTreeNode> {
+    let all: HashMap<u32, &ProcessDto> = processes.into_iter().map(|p| (p.pid, p)).collect();
+    let visible: HashMap<u32, &ProcessDto> = all
+        .iter()
+        .filter(|(_, p)| filter.matches(p, current_user))
+        .map(|(pid, p)| (*pid, *p))
+        .collect();
+
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    let mut roots = Vec::new();
+    for pid in visible.keys() {
+        match visible_ancestor(*pid, &all, &visible) {
+            Some(parent) => children.entry(parent).or_default().push(*pid),
+            None => roots.push(*pid),
+        }
+    }
+
+    let mut builder = TreeBuilder {
+        visible: &visible,
+        children: &children,
+        sort,
+        placed: HashSet::new(),
+    };
+    let mut forest = builder.build_level(roots);
+
+    // Parent links that form a cycle (possible after PID reuse) leave nodes
+    // unreachable from any root; surface them as roots instead of dropping them.
+    let mut unplaced: Vec<u32> = visible
+        .keys()
+        .filter(|pid| !builder.placed.contains(pid))
+        .copied()
+        .collect();
+    unplaced.sort_unstable();
+    for pid in unplaced {
+        if !builder.placed.contains(&pid) {
+            forest.extend(builder.build_level(vec![pid]));
+        }
+    }
+    forest.sort_by(|a, b| compare_processes(&a.process, &b.process, sort));
+    forest
+}
+
+/// `root` and all of its descendants, ordered for signalling.
+///
+/// `ParentFirst` yields a breadth-first walk from the root; `ChildrenFirst`
+/// is the exact reverse, so every process comes before its parent.
+pub fn subtree_pids<'a>(
+    processes: impl IntoIterator<Item = &'a ProcessDto>,
+    root: u32,
+    order: TreeKillOrder,
+) -> Vec<u32> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for p in processes {
+        if let Some(parent) = p.parent_pid {
+            children.entry(parent).or_default().push(p.pid);
+        }
+    }
+    for list in children.values_mut() {
+        list.sort_unstable();
+    }
+
+    let mut seen = HashSet::from([root]);
+    let mut pids = vec![root];
+    let mut next = 0;
+    while let Some(&pid) = pids.get(next) {
+        next += 1;
+        for child in children.get(&pid).into_iter().flatten() {
+            if seen.insert(*child) {
+                pids.push(*child);
+            }
+        }
+    }
+
+    if order == TreeKillOrder::ChildrenFirst {
+        pids.reverse();
+    }
+    pids
+}
+
+/// Walk up `parent_pid` links until a visible process is found.
+fn visible_ancestor(
+    pid: u32,
+    all: &HashMap<u32, &ProcessDto>,
+    visible: &HashMap<u32, &ProcessDto>,
+) -> Option<u32> {
+    let mut seen = HashSet::from([pid]);
+    let mut current = all.get(&pid)?.parent_pid;
+    while let Some(parent) = current {
+        if !seen.insert(parent) {
+            return None;
+        }
+        if visible.contains_key(&parent) {
+            return Some(parent);
+        }
+        current = all.get(&parent).and_then(|p| p.parent_pid);
+    }
+    None
+}
+
+struct TreeBuilder<'a> {
+    visible: &'a HashMap<u32, &'a ProcessDto>,
+    children: &'a HashMap<u32, Vec<u32>>,
+    sort: &'a [SortSpec],
+    placed: HashSet<u32>,
+}
+
+impl TreeBuilder<'_> {
+    fn build_level(&mut self, pids: Vec<u32>) -> Vec<ProcessTreeNode> {
+        let mut nodes: Vec<ProcessTreeNode> = pids
+            .into_iter()
+            .filter_map(|pid| self.build_node(pid))
+            .collect();
+        nodes.sort_by(|a, b| compare_processes(&a.process, &b.process, self.sort));
+        nodes
+    }
+
+    fn build_node(&mut self, pid: u32) -> Option<ProcessTreeNode> {
+        if !self.placed.insert(pid) {
+            return None;
+        }
+        let process = (*self.visible.get(&pid)?).clone();
+        let child_pids = self.children.get(&pid).cloned().unwrap_or_default();
+        let children = self.build_level(child_pids);
+
+        let subtree_cpu_percent =
+            process.cpu_percent + children.iter().map(|c| c.subtree_cpu_percent).sum::<f32>();
+        let subtree_memory_bytes =
+            process.memory_bytes + children.iter().map(|c| c.subtree_memory_bytes).sum::<u64>();
+        let descendant_count =
+            children.len() + children.iter().map(|c| c.descendant_count).sum::<usize>();
+
+        Some(ProcessTreeNode {
+            process,
+            children,
+            subtree_cpu_percent,
+            subtree_memory_bytes,
+            descendant_count,
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{SearchMode, SortDirection, SortField};
+
+    fn make_proc(pid: u32, parent: Option<u32>, name: &str, cpu: f32, mem: u64) -> ProcessDto {
+        ProcessDto {
+            cpu_percent: cpu,
+            memory_bytes: mem,
+            user: Some("alice".to_string()),
+            parent_pid: parent,
+            ..ProcessDto::test(pid, name)
+        }
+    }
+
+    fn by_pid() -> Vec<SortSpec> {
+        vec![SortSpec {
+            field: SortField::Pid,
+            direction: SortDirection::Asc,
+        }]
+    }
+
+    #[test]
+    fn test_tree_nests_and_aggregates() {
+        let procs = vec![
+            make_proc(1, None, "init", 1.0, 100),
+            make_proc(10, Some(1), "make", 2.0, 200),
+            make_proc(11, Some(10), "cc1", 30.0, 300),
+            make_proc(12, Some(10), "cc1", 40.0, 400),
+        ];
+        let forest = build_process_tree(&procs, &ProcessFilter::default(), "alice", &by_pid());
+        assert_eq!(forest.len(), 1);
+        let init = &forest[0];
+        assert_eq!(init.descendant_count, 3);
+        assert_eq!(init.subtree_memory_bytes, 1000);
+        assert!((init.subtree_cpu_percent - 73.0).abs() < 0.001);
+
+        let make = &init.children[0];
+        assert_eq!(make.process.pid, 10);
+        assert_eq!(make.descendant_count, 2);
+        assert_eq!(make.children[0].process.pid, 11);
+        assert_eq!(make.children[1].process.pid, 12);
+    }
+
+    #[test]
+    fn test_tree_orphans_become_roots() {
+        let procs = vec![
+            make_proc(1, None, "init", 0.0, 0),
+            make_proc(50, Some(999), "orphan", 0.0, 0),
+        ];
+        let forest = build_process_tree(&procs, &ProcessFilter::default(), "alice", &by_pid());
+        let roots: Vec<u32> = forest.iter().map(|n| n.process.pid).collect();
+        assert_eq!(roots, vec![1, 50]);
+    }
+
+    #[test]
+    fn test_tree_reparents_past_hidden_ancestors() {
+        let procs = vec![
+            make_proc(1, None, "init", 0.0, 0),
+            make_proc(10, Some(1), "bash", 0.0, 0),
+            make_proc(11, Some(10), "python", 0.0, 0),
+            make_proc(12, Some(11), "python", 0.0, 0),
+        ];
+        let filter = ProcessFilter {
+            search: Some("python".to_string()),
+            ..Default::default()
+        };
+        let forest = build_process_tree(&procs, &filter, "alice", &by_pid());
+        assert_eq!(forest.len(), 1);
+        assert_eq!(forest[0].process.pid, 11);
+        assert_eq!(forest[0].children[0].process.pid, 12);
+
+        // With the middle python hidden, 12 hangs directly off init.
+        let filter = ProcessFilter {
+            search: Some("^(init|vim)$".to_string()),
+            search_mode: SearchMode::Regex,
+            ..Default::default()
+        };
+        let procs = vec![
+            make_proc(1, None, "init", 0.0, 0),
+            make_proc(10, Some(1), "bash", 0.0, 0),
+            make_proc(12, Some(10), "vim", 0.0, 0),
+        ];
+        let forest = build_process_tree(&procs, &filter, "alice", &by_pid());
+        assert_eq!(forest[0].process.pid, 1);
+        assert_eq!(forest[0].children[0].process.pid, 12);
+    }
+
+    #[test]
+    fn test_tree_survives_parent_cycle() {
+        let procs = vec![
+            make_proc(20, Some(21), "a", 0.0, 0),
+            make_proc(21, Some(20), "b", 0.0, 0),
+        ];
+        let forest = build_process_tree(&procs, &ProcessFilter::default(), "alice", &by_pid());
+        assert_eq!(forest.len(), 1);
+        assert_eq!(forest[0].descendant_count, 1);
+    }
+
+    #[test]
+    fn test_subtree_pids_order() {
+        let procs = vec![
+            make_proc(1, None, "init", 0.0, 0),
+            make_proc(10, Some(1), "make", 0.0, 0),
+            make_proc(11, Some(10), "sh", 0.0, 0),
+            make_proc(12, Some(10), "cc1", 0.0, 0),
+            make_proc(13, Some(11), "cc1", 0.0, 0),
+            make_proc(20, Some(1), "other", 0.0, 0),
+        ];
+        assert_eq!(
+            subtree_pids(&procs, 10, TreeKillOrder::ParentFirst),
+            vec![10, 11, 12, 13]
+        );
+        assert_eq!(
+            subtree_pids(&procs, 10, TreeKillOrder::ChildrenFirst),
+            vec![13, 12, 11, 10]
+        );
+        assert_eq!(
+            subtree_pids(&procs, 20, TreeKillOrder::ChildrenFirst),
+            vec![20]
+        );
+    }
+
+    #[test]
+    fn test_tree_sorts_siblings() {
+        let procs = vec![
+            make_proc(1, None, "init", 0.0, 0),
+            make_proc(2, Some(1), "low", 1.0, 0),
+            make_proc(3, Some(1), "high", 9.0, 0),
+        ];
+        let sort = [SortSpec {
+            field: SortField::CpuPercent,
+            direction: SortDirection::Desc,
+        }];
+        let forest = build_process_tree(&procs, &ProcessFilter::default(), "alice", &sort);
+        assert_eq!(forest[0].children[0].process.pid, 3);
+    }
+}
@@ -0,0 +1,324 @@
+use std::io;
+
+#[cfg(target_os = "linux")]
+use crate::{
+    affinity::{for_other_threads, join_tids},
+    models::{IoPriority, IoPriorityClass},
+};
+use crate::{error::AppError, models::SchedulingPolicy};
+
+/// Highest priority a nice value can express.
+pub const NICE_MIN: i32 = -20;
+/// Lowest priority a nice value can express.
+pub const NICE_MAX: i32 = 19;
+/// Lowest level within an I/O priority class (0 is highest).
+pub const IO_LEVEL_MAX: u8 = 7;
+
+#[cfg(target_os = "linux")]
+const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+#[cfg(target_os = "linux")]
+const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+
+/// Current nice value of `pid`, or None if it cannot be read.
+pub fn get_nice(pid: u32) -> Option<i32> {
+    // -1 is a valid nice value, so errno is the only way to tell failure apart.
+    clear_errno();
+    let nice = unsafe { libc::getpriority(libc::PRIO_PROCESS, pid as libc::id_t) };
+    if nice == -1 && io::Error::last_os_error().raw_os_error().unwrap_or(0) != 0 {
+        return None;
+    }
+    Some(nice)
+}
+
+/// Change the nice value of `pid` with `setpriority(2)`.
+///
+/// Lowering the value (raising priority) needs `CAP_SYS_NICE` or root; the
+/// kernel reports that as `EACCES`, which maps to `PermissionDenied` just
+/// like renicing another user's process (`EPERM`).
+///
+/// On Linux the nice value is per thread, so every thread listed under
+/// `/proc/<pid>/task` is updated. If some of them fail after the main thread
+/// succeeded, the error names the threads that kept their old value.
+pub fn set_nice(pid: u32, nice: i32) -> Result<(), AppError> {
+    if !(NICE_MIN..=NICE_MAX).contains(&nice) {
+        return Err(AppError::InvalidPriority { value: nice });
+    }
+
+    set_thread_nice(pid, nice).map_err(|e| map_nice_errno(pid, nice, e))?;
+
+    #[cfg(target_os = "linux")]
+    for_other_threads(pid, |tid| set_thread_nice(tid, nice)).map_err(|(tids, err)| {
+        map_nice_errno(pid, nice, err)
+            .partial(&format!("threads {} were not reniced", join_tids(&tids)))
+    })?;
+    Ok(())
+}
+
+fn set_thread_nice(tid: u32, nice: i32) -> io::Result<()> {
+    let ret = unsafe { libc::setpriority(libc::PRIO_PROCESS, tid as libc::id_t, nice) };
+    if ret == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+fn map_nice_errno(pid: u32, nice: i32, err: io::Error) -> AppError {
+    match err.raw_os_error() {
+        Some(libc::EACCES) => AppError::PermissionDenied {
+            pid,
+            message: format!("Raising priority to nice {nice} requires elevated privileges"),
+        },
+        Some(libc::EPERM) => AppError::PermissionDenied {
+            pid,
+            message: "Insufficient permissions to change this process's priority".to_string(),
+        },
+        Some(libc::ESRCH) => AppError::NotFound { pid },
+        _ => AppError::OsError {
+            message: err.to_string(),
+        },
+    }
+}
+
+/// Scheduling policy of `pid` from `sched_getscheduler(2)`.
+#[cfg(target_os = "linux")]
+pub fn scheduling_policy(pid: u32) -> Option<SchedulingPolicy> {
+    let raw = unsafe { libc::sched_getscheduler(pid as libc::pid_t) };
+    (raw >= 0).then(|| policy_from_raw(raw)).flatten()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn scheduling_policy(_pid: u32) -> Option<SchedulingPolicy> {
+    None
+}
+
+/// Map a Linux `SCHED_*` constant (as in `stat` field 41) to the DTO enum.
+#[cfg(target_os = "linux")]
+pub fn policy_from_raw(raw: i32) -> Option<SchedulingPolicy> {
+    // SCHED_RESET_ON_FORK may be or-ed into the value.
+    match raw & !libc::SCHED_RESET_ON_FORK {
+        libc::SCHED_OTHER => Some(SchedulingPolicy::Other),
+        libc::SCHED_FIFO => Some(SchedulingPolicy::Fifo),
+        libc::SCHED_RR => Some(SchedulingPolicy::RoundRobin),
+        libc::SCHED_BATCH => Some(SchedulingPolicy::Batch),
+        libc::SCHED_IDLE => Some(SchedulingPolicy::Idle),
+        libc::SCHED_DEADLINE => Some(SchedulingPolicy::Deadline),
+        _ => None,
+    }
+}
+
+/// I/O scheduling class and level of `pid` from `ioprio_get(2)`.
+#[cfg(target_os = "linux")]
+pub fn get_io_priority(pid: u32) -> Option<IoPriority> {
+    let ret =
+        unsafe { libc::syscall(libc::SYS_ioprio_get, IOPRIO_WHO_PROCESS, pid as libc::c_int) };
+    (ret >= 0).then(|| decode_io_priority(ret as libc::c_int))
+}
+
+/// Set the I/O priority of `pid` with `ioprio_set(2)`, as `ionice -p` does.
+///
+/// The real-time class needs `CAP_SYS_ADMIN`; `level` is ignored by the
+/// kernel for the idle class.
+#[cfg(target_os = "linux")]
+pub fn set_io_priority(pid: u32, class: IoPriorityClass, level: u8) -> Result<(), AppError> {
+    if level > IO_LEVEL_MAX {
+        return Err(AppError::InvalidPriority {
+            value: i32::from(level),
+        });
+    }
+
+    let ret = unsafe {
+        libc::syscall(
+            libc::SYS_ioprio_set,
+            IOPRIO_WHO_PROCESS,
+            pid as libc::c_int,
+            encode_io_priority(class, level),
+        )
+    };
+    if ret == 0 {
+        return Ok(());
+    }
+
+    let err = io::Error::last_os_error();
+    match err.raw_os_error() {
+        Some(libc::EPERM) => Err(AppError::PermissionDenied {
+            pid,
+            message: "Insufficient permissions to change this process's I/O priority".to_string(),
+        }),
+        Some(libc::ESRCH) => Err(AppError::NotFound { pid }),
+        _ => Err(AppError::OsError {
+            message: err.to_string(),
+        }),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn encode_io_priority(class: IoPriorityClass, level: u8) -> libc::c_int {
+    let class = match class {
+        IoPriorityClass::None => 0,
+        IoPriorityClass::Realtime => 1,
+        IoPriorityClass::BestEffort => 2,
+        IoPriorityClass::Idle => 3,
+    };
+    (class << IOPRIO_CLASS_SHIFT) | libc::c_int::from(level)
+}
+
+#[cfg(target_os = "linux")]
+fn decode_io_priority(value: libc::c_int) -> IoPriority {
+    let class = match value >> IOPRIO_CLASS_SHIFT {
+        1 => IoPriorityClass::Realtime,
+        2 => IoPriorityClass::BestEffort,
+        3 => IoPriorityClass::Idle,
+        _ => IoPriorityClass::None,
+    };
+    IoPriority {
+        class,
+        level: (value & ((1 << IOPRIO_CLASS_SHIFT) - 1)) as u8,
+    }
+}
+
+fn clear_errno() {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    unsafe {
+        *libc::__errno_location() = 0;
+    }
+    #[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+    unsafe {
+        *libc::__error() = 0;
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::{affinity::list_tids, collector::PROC_ROOT};
+    use std::{path::Path, process::Command};
+
+    #[test]
+    fn test_renice_child() {
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        let pid = child.id();
+        assert_eq!(get_nice(pid), Some(get_nice(std::process::id()).unwrap()));
+        assert_eq!(scheduling_policy(pid), Some(SchedulingPolicy::Other));
+
+        set_nice(pid, NICE_MAX).unwrap();
+        assert_eq!(get_nice(pid), Some(NICE_MAX));
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+        assert!(matches!(set_nice(pid, 10), Err(AppError::NotFound { .. })));
+        assert_eq!(get_nice(pid), None);
+    }
+
+    /// Body of the multi-threaded child spawned by `test_renice_all_threads`;
+    /// a no-op in normal test runs.
+    #[test]
+    fn threaded_child() {
+        if std::env::var_os("PM_THREADED_CHILD").is_none() {
+            return;
+        }
+        for _ in 0..3 {
+            std::thread::spawn(|| std::thread::sleep(std::time::Duration::from_secs(30)));
+        }
+        std::thread::sleep(std::time::Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_renice_all_threads() {
+        let mut child = Command::new(std::env::current_exe().unwrap())
+            .args([
+                "--exact",
+                "priority::tests::threaded_child",
+                "--test-threads=1",
+            ])
+            .env("PM_THREADED_CHILD", "1")
+            .stdout(std::process::Stdio::null())
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        let proc_root = Path::new(PROC_ROOT);
+        for _ in 0..100 {
+            if list_tids(proc_root, pid).len() >= 4 {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        let tids = list_tids(proc_root, pid);
+        assert!(tids.len() >= 4);
+
+        set_nice(pid, NICE_MAX).unwrap();
+        for tid in tids {
+            assert_eq!(get_nice(tid), Some(NICE_MAX), "thread {tid}");
+        }
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+
+    #[test]
+    fn test_set_nice_rejects_out_of_range() {
+        assert!(matches!(
+            set_nice(std::process::id(), 20),
+            Err(AppError::InvalidPriority { value: 20 })
+        ));
+        assert!(matches!(
+            set_nice(std::process::id(), -21),
+            Err(AppError::InvalidPriority { value: -21 })
+        ));
+    }
+
+    #[test]
+    fn test_io_priority_round_trip() {
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        let pid = child.id();
+        assert!(get_io_priority(pid).is_some());
+
+        set_io_priority(pid, IoPriorityClass::BestEffort, 6).unwrap();
+        assert_eq!(
+            get_io_priority(pid),
+            Some(IoPriority {
+                class: IoPriorityClass::BestEffort,
+                level: 6
+            })
+        );
+        set_io_priority(pid, IoPriorityClass::Idle, 0).unwrap();
+        assert_eq!(get_io_priority(pid).unwrap().class, IoPriorityClass::Idle);
+
+        assert!(matches!(
+            set_io_priority(pid, IoPriorityClass::BestEffort, 8),
+            Err(AppError::InvalidPriority { value: 8 })
+        ));
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+
+    #[test]
+    fn test_io_priority_encoding() {
+        let value = encode_io_priority(IoPriorityClass::Realtime, 3);
+        assert_eq!(value, (1 << 13) | 3);
+        assert_eq!(
+            decode_io_priority(value),
+            IoPriority {
+                class: IoPriorityClass::Realtime,
+                level: 3
+            }
+        );
+        assert_eq!(decode_io_priority(0).class, IoPriorityClass::None);
+    }
+
+    #[test]
+    fn test_policy_from_raw() {
+        assert_eq!(policy_from_raw(0), Some(SchedulingPolicy::Other));
+        assert_eq!(policy_from_raw(2), Some(SchedulingPolicy::RoundRobin));
+        assert_eq!(
+            policy_from_raw(libc::SCHED_FIFO | libc::SCHED_RESET_ON_FORK),
+            Some(SchedulingPolicy::Fifo)
+        );
+        assert_eq!(policy_from_raw(42), None);
+    }
+}
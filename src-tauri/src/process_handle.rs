@@ -0,0 +1,291 @@
+use std::io;
+
+#[cfg(target_os = "linux")]
+use std::{
+    os::fd::{AsRawFd, FromRawFd, OwnedFd},
+    path::Path,
+};
+
+#[cfg(target_os = "linux")]
+use crate::collector::{
+    procfs_collector::{read_state, start_ticks_to_unix},
+    PROC_ROOT,
+};
+use crate::error::AppError;
+
+/// A process identified by PID *and* start time, verified before every signal.
+///
+/// On Linux the handle holds a pidfd: signals sent through it can only reach
+/// the process that was verified, even if the PID is recycled afterwards.
+/// Kernels without `pidfd_open` (< 5.3) fall back to comparing the raw
+/// `starttime` jiffies read at open with the live value immediately before
+/// `kill(2)`; other Unixes only re-check existence.
+pub struct ProcessHandle {
+    pid: u32,
+    /// `starttime` (clock ticks since boot) of the verified process
+    #[cfg(target_os = "linux")]
+    start_ticks: u64,
+    #[cfg(target_os = "linux")]
+    pidfd: Option<OwnedFd>,
+}
+
+impl ProcessHandle {
+    /// Open `pid`, failing with `IdentityMismatch` if it no longer started at
+    /// `expected_start_time`. With no expected time only existence is checked.
+    #[cfg_attr(not(target_os = "linux"), allow(unused_variables))]
+    pub fn open(pid: u32, expected_start_time: Option<u64>) -> Result<Self, AppError> {
+        if pid == 0 || pid > i32::MAX as u32 {
+            return Err(AppError::InvalidPid { pid });
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            let pidfd = match pidfd_open(pid) {
+                Ok(fd) => Some(fd),
+                Err(e) if e.raw_os_error() == Some(libc::ENOSYS) => None,
+                Err(e) => return Err(map_errno(pid, e)),
+            };
+            // Read after opening the pidfd: if the PID was recycled in between,
+            // the start time belongs to the new process and won't match.
+            let (_, start_ticks) =
+                read_state(Path::new(PROC_ROOT), pid).ok_or(AppError::NotFound { pid })?;
+            if let Some(expected) = expected_start_time {
+                let actual = start_ticks_to_unix(Path::new(PROC_ROOT), start_ticks)
+                    .ok_or(AppError::NotFound { pid })?;
+                if actual != expected {
+                    return Err(AppError::IdentityMismatch {
+                        pid,
+                        expected_start_time: expected,
+                        actual_start_time: actual,
+                    });
+                }
+            }
+            Ok(Self {
+                pid,
+                start_ticks,
+                pidfd,
+            })
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let handle = Self { pid };
+            handle.verify()?;
+            Ok(handle)
+        }
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Deliver `sig` to the verified process.
+    pub fn signal(&self, sig: libc::c_int) -> Result<(), AppError> {
+        #[cfg(target_os = "linux")]
+        if let Some(fd) = &self.pidfd {
+            return pidfd_send_signal(fd, sig).map_err(|e| map_errno(self.pid, e));
+        }
+
+        self.verify()?;
+        let ret = unsafe { libc::kill(self.pid as libc::pid_t, sig) };
+        if ret == 0 {
+            Ok(())
+        } else {
+            Err(map_errno(self.pid, io::Error::last_os_error()))
+        }
+    }
+
+    /// True until the process exits. Zombies count as exited: only their
+    /// exit status is left.
+    pub fn is_running(&self) -> bool {
+        #[cfg(target_os = "linux")]
+        if let Some(fd) = &self.pidfd {
+            // A pidfd becomes readable once the process has terminated.
+            let mut pfd = libc::pollfd {
+                fd: fd.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            let ret = unsafe { libc::poll(&mut pfd, 1, 0) };
+            return ret == 0;
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            match read_state(Path::new(PROC_ROOT), self.pid) {
+                Some(('Z' | 'X', _)) => false,
+                Some(_) => self.verify().is_ok(),
+                None => false,
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let ret = unsafe { libc::kill(self.pid as libc::pid_t, 0) };
+            ret == 0 || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+        }
+    }
+
+    /// Compare the live `starttime` jiffies with the ones read at open.
+    #[cfg(target_os = "linux")]
+    fn verify(&self) -> Result<(), AppError> {
+        let proc_root = Path::new(PROC_ROOT);
+        let (_, live) =
+            read_state(proc_root, self.pid).ok_or(AppError::NotFound { pid: self.pid })?;
+        if live == self.start_ticks {
+            return Ok(());
+        }
+        let to_unix = |ticks| start_ticks_to_unix(proc_root, ticks).unwrap_or_default();
+        Err(AppError::IdentityMismatch {
+            pid: self.pid,
+            expected_start_time: to_unix(self.start_ticks),
+            actual_start_time: to_unix(live),
+        })
+    }
+
+    /// Start times are not readable without sysinfo here; existence is still checked.
+    #[cfg(not(target_os = "linux"))]
+    fn verify(&self) -> Result<(), AppError> {
+        let ret = unsafe { libc::kill(self.pid as libc::pid_t, 0) };
+        if ret == 0 || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM) {
+            Ok(())
+        } else {
+            Err(AppError::NotFound { pid: self.pid })
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn pidfd_open(pid: u32) -> io::Result<OwnedFd> {
+    let ret = unsafe { libc::syscall(libc::SYS_pidfd_open, pid as libc::pid_t, 0) };
+    if ret < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(unsafe { OwnedFd::from_raw_fd(ret as libc::c_int) })
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn pidfd_send_signal(fd: &OwnedFd, sig: libc::c_int) -> io::Result<()> {
+    let ret = unsafe {
+        libc::syscall(
+            libc::SYS_pidfd_send_signal,
+            fd.as_raw_fd(),
+            sig,
+            std::ptr::null::<libc::siginfo_t>(),
+            0,
+        )
+    };
+    if ret < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+fn map_errno(pid: u32, err: io::Error) -> AppError {
+    match err.raw_os_error() {
+        Some(libc::EPERM) => AppError::PermissionDenied {
+            pid,
+            message: "Insufficient permissions to signal this process".to_string(),
+        },
+        Some(libc::ESRCH) => AppError::NotFound { pid },
+        _ => AppError::OsError {
+            message: err.to_string(),
+        },
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::collector::procfs_collector::read_start_time;
+    use std::{
+        process::{Child, Command},
+        thread,
+        time::Duration,
+    };
+
+    fn spawn_sleep() -> Child {
+        Command::new("sleep").arg("30").spawn().unwrap()
+    }
+
+    #[test]
+    fn test_open_verifies_start_time() {
+        let mut child = spawn_sleep();
+        let pid = child.id();
+        let start = read_start_time(Path::new(PROC_ROOT), pid).unwrap();
+
+        assert!(ProcessHandle::open(pid, Some(start)).is_ok());
+        assert!(ProcessHandle::open(pid, None).is_ok());
+        assert!(matches!(
+            ProcessHandle::open(pid, Some(start - 1)),
+            Err(AppError::IdentityMismatch { actual_start_time, .. }) if actual_start_time == start
+        ));
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+
+    #[test]
+    fn test_fallback_compares_start_ticks() {
+        let mut child = spawn_sleep();
+        let pid = child.id();
+        let (_, start_ticks) = read_state(Path::new(PROC_ROOT), pid).unwrap();
+
+        // Without a pidfd, a process started one tick later in the same
+        // second must still be told apart.
+        let stale = ProcessHandle {
+            pid,
+            start_ticks: start_ticks - 1,
+            pidfd: None,
+        };
+        assert!(!stale.is_running());
+        assert!(matches!(
+            stale.signal(libc::SIGKILL),
+            Err(AppError::IdentityMismatch { .. })
+        ));
+        let same = ProcessHandle {
+            pid,
+            start_ticks,
+            pidfd: None,
+        };
+        assert!(same.is_running());
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+
+    #[test]
+    fn test_signal_and_exit_detection() {
+        let mut child = spawn_sleep();
+        let pid = child.id();
+        let handle = ProcessHandle::open(pid, None).unwrap();
+        assert!(handle.is_running());
+
+        handle.signal(libc::SIGKILL).unwrap();
+        thread::sleep(Duration::from_millis(100));
+        // Still an unreaped zombie here, which must count as exited.
+        assert!(!handle.is_running());
+
+        child.wait().unwrap();
+        assert!(matches!(
+            handle.signal(libc::SIGKILL),
+            Err(AppError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_open_rejects_bad_pids() {
+        assert!(matches!(
+            ProcessHandle::open(0, None),
+            Err(AppError::InvalidPid { pid: 0 })
+        ));
+        assert!(matches!(
+            ProcessHandle::open(u32::MAX, None),
+            Err(AppError::InvalidPid { .. })
+        ));
+    }
+}
@@ -0,0 +1,287 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+#[cfg(target_os = "linux")]
+use crate::collector::{procfs_collector::read_start_time, PROC_ROOT};
+use crate::models::{ProcessDto, ProtectionEntry, ProtectionRule};
+
+/// File the user's rules are saved to, next to the audit log.
+const RULES_FILE: &str = "protection.json";
+
+/// Display servers, compositors and session managers whose death takes the
+/// whole desktop session down with them.
+const SESSION_CRITICAL: &[&str] = &[
+    // Linux
+    "Xorg",
+    "Xwayland",
+    "gnome-shell",
+    "kwin_wayland",
+    "kwin_x11",
+    "plasmashell",
+    "mutter",
+    "sway",
+    "Hyprland",
+    "weston",
+    "wayfire",
+    "labwc",
+    "niri",
+    "gdm",
+    "sddm",
+    "lightdm",
+    "systemd-logind",
+    // macOS
+    "WindowServer",
+    "loginwindow",
+    // Windows
+    "dwm.exe",
+    "csrss.exe",
+    "smss.exe",
+    "wininit.exe",
+    "winlogon.exe",
+    "lsass.exe",
+    "services.exe",
+];
+
+/// Processes that kill-type actions refuse to touch unless forced.
+///
+/// Built-in rules cover init, the manager itself, the process that started it,
+/// its session leader and well-known display servers; the user adds more.
+pub struct ProtectionList {
+    builtin: Vec<ProtectionEntry>,
+    custom: Vec<ProtectionRule>,
+    /// Where `custom` is saved; None keeps it in memory only
+    path: Option<PathBuf>,
+}
+
+impl ProtectionList {
+    pub fn new(builtin: Vec<ProtectionEntry>) -> Self {
+        Self {
+            builtin,
+            custom: Vec::new(),
+            path: None,
+        }
+    }
+
+    /// Load the user's rules from `<dir>/protection.json` and save later
+    /// changes there. A missing file means no rules yet.
+    pub fn load(&mut self, dir: &Path) -> io::Result<()> {
+        let path = dir.join(RULES_FILE);
+        let result = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).map(|rules| self.custom = rules),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(e) => return Err(e),
+        };
+        self.path = Some(path);
+        result.map_err(io::Error::from)
+    }
+
+    /// Built-in rules followed by the user's.
+    pub fn entries(&self) -> Vec<ProtectionEntry> {
+        let custom = self.custom.iter().map(|rule| ProtectionEntry {
+            rule: rule.clone(),
+            builtin: false,
+            reason: "on the protection list".to_string(),
+            start_time: None,
+        });
+        self.builtin.iter().cloned().chain(custom).collect()
+    }
+
+    /// Replace the user-defined rules, saving them first if the list was
+    /// loaded from disk. On a write error the old rules stay in effect.
+    pub fn set_custom(&mut self, rules: Vec<ProtectionRule>) -> io::Result<()> {
+        if let Some(path) = &self.path {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            // Write then rename so a crash never leaves a truncated file.
+            let tmp = path.with_extension("json.tmp");
+            fs::write(&tmp, serde_json::to_vec_pretty(&rules)?)?;
+            fs::rename(&tmp, path)?;
+        }
+        self.custom = rules;
+        Ok(())
+    }
+
+    /// Why `p` is protected, or None if it may be signalled.
+    pub fn reason(&self, p: &ProcessDto) -> Option<&str> {
+        if let Some(entry) = self.builtin.iter().find(|e| entry_matches(e, p)) {
+            return Some(&entry.reason);
+        }
+        self.custom
+            .iter()
+            .any(|rule| rule_matches(rule, p))
+            .then_some("on the protection list")
+    }
+}
+
+impl Default for ProtectionList {
+    fn default() -> Self {
+        Self::new(default_rules())
+    }
+}
+
+/// A built-in entry tied to a start time only matches that instance of the
+/// PID. Processes whose start time is unknown are still treated as matching.
+fn entry_matches(entry: &ProtectionEntry, p: &ProcessDto) -> bool {
+    rule_matches(&entry.rule, p)
+        && match (entry.start_time, p.start_time) {
+            (Some(expected), Some(actual)) => expected == actual,
+            _ => true,
+        }
+}
+
+fn rule_matches(rule: &ProtectionRule, p: &ProcessDto) -> bool {
+    match rule {
+        ProtectionRule::Pid(pid) => p.pid == *pid,
+        ProtectionRule::Name(name) => p.name.eq_ignore_ascii_case(name),
+        ProtectionRule::Path(path) => p.path.as_deref() == Some(path.as_str()),
+        ProtectionRule::User(user) => p.user.as_deref() == Some(user.as_str()),
+    }
+}
+
+fn default_rules() -> Vec<ProtectionEntry> {
+    let builtin = |rule, reason: &str| ProtectionEntry {
+        rule,
+        builtin: true,
+        reason: reason.to_string(),
+        start_time: None,
+    };
+    // PIDs get reused once their process exits (the parent in particular may
+    // go away while the manager keeps running), so pin each to its instance.
+    let pid_rule = |pid: u32, reason: &str| ProtectionEntry {
+        start_time: pid_start_time(pid),
+        ..builtin(ProtectionRule::Pid(pid), reason)
+    };
+
+    let mut rules = vec![pid_rule(std::process::id(), "the process manager itself")];
+
+    #[cfg(unix)]
+    {
+        rules.push(builtin(ProtectionRule::Pid(1), "init"));
+        rules.push(pid_rule(
+            std::os::unix::process::parent_id(),
+            "parent of the process manager",
+        ));
+        let sid = unsafe { libc::getsid(0) };
+        if sid > 1 {
+            rules.push(pid_rule(sid as u32, "session leader"));
+        }
+    }
+
+    rules.extend(SESSION_CRITICAL.iter().map(|name| {
+        builtin(
+            ProtectionRule::Name(name.to_string()),
+            "display server or session manager",
+        )
+    }));
+    rules
+}
+
+/// Start time of `pid` as reported in `ProcessDto::start_time`, where known.
+#[cfg(target_os = "linux")]
+fn pid_start_time(pid: u32) -> Option<u64> {
+    read_start_time(Path::new(PROC_ROOT), pid)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn pid_start_time(_pid: u32) -> Option<u64> {
+    None
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_proc(pid: u32, name: &str, user: &str) -> ProcessDto {
+        ProcessDto {
+            user: Some(user.to_string()),
+            path: Some(format!("/usr/bin/{}", name)),
+            ..ProcessDto::test(pid, name)
+        }
+    }
+
+    #[test]
+    fn test_builtin_defaults() {
+        let list = ProtectionList::default();
+        assert_eq!(
+            list.reason(&make_proc(std::process::id(), "pm", "alice")),
+            Some("the process manager itself")
+        );
+        assert_eq!(
+            list.reason(&make_proc(4242, "xorg", "root")),
+            Some("display server or session manager")
+        );
+        #[cfg(unix)]
+        assert_eq!(list.reason(&make_proc(1, "systemd", "root")), Some("init"));
+        assert!(list.entries().iter().all(|e| e.builtin));
+        #[cfg(target_os = "linux")]
+        assert!(list.entries()[0].start_time.is_some());
+    }
+
+    #[test]
+    fn test_builtin_pid_rule_ignores_reused_pid() {
+        let list = ProtectionList::new(vec![ProtectionEntry {
+            rule: ProtectionRule::Pid(4242),
+            builtin: true,
+            reason: "parent of the process manager".to_string(),
+            start_time: Some(1_700_000_000),
+        }]);
+        let at = |start_time| ProcessDto {
+            start_time,
+            ..make_proc(4242, "bash", "alice")
+        };
+        assert!(list.reason(&at(Some(1_700_000_000))).is_some());
+        assert!(list.reason(&at(Some(1_700_000_500))).is_none());
+        assert!(list.reason(&at(None)).is_some());
+    }
+
+    #[test]
+    fn test_custom_rules() {
+        let mut list = ProtectionList::new(Vec::new());
+        let p = make_proc(900_000, "postgres", "postgres");
+        assert_eq!(list.reason(&p), None);
+
+        list.set_custom(vec![ProtectionRule::User("postgres".to_string())])
+            .unwrap();
+        assert_eq!(list.reason(&p), Some("on the protection list"));
+
+        list.set_custom(vec![ProtectionRule::Path("/usr/bin/postgres".to_string())])
+            .unwrap();
+        assert!(list.reason(&p).is_some());
+        assert!(list
+            .reason(&make_proc(900_001, "psql", "postgres"))
+            .is_none());
+        assert_eq!(list.entries().len(), 1);
+    }
+
+    #[test]
+    fn test_custom_rules_persist() {
+        let dir = std::env::temp_dir().join(format!("pm-protection-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let rules = vec![
+            ProtectionRule::Name("postgres".to_string()),
+            ProtectionRule::Pid(4242),
+        ];
+
+        let mut list = ProtectionList::new(Vec::new());
+        list.load(&dir).unwrap();
+        assert!(list.entries().is_empty());
+        list.set_custom(rules.clone()).unwrap();
+
+        // As after a restart.
+        let mut reloaded = ProtectionList::new(Vec::new());
+        reloaded.load(&dir).unwrap();
+        let saved: Vec<_> = reloaded.entries().into_iter().map(|e| e.rule).collect();
+        assert_eq!(saved, rules);
+
+        fs::write(dir.join(RULES_FILE), "not json").unwrap();
+        assert!(ProtectionList::new(Vec::new()).load(&dir).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
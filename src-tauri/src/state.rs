@@ -1,26 +1,135 @@
 use std::collections::HashMap;
+#[cfg(target_os = "linux")]
+use std::path::PathBuf;
 
-use crate::models::ProcessDto;
+use crate::{
+    audit::AuditLog,
+    history::ProcessHistory,
+    models::{ProcessDto, SystemSnapshot},
+    protection::ProtectionList,
+};
 
 /// Shared application state — kept behind an `Arc<Mutex<AppState>>` in Tauri.
 pub struct AppState {
     /// Latest process snapshot (PID → DTO).
     pub snapshot: HashMap<u32, ProcessDto>,
+    /// Recent per-PID resource samples, appended on every updater tick.
+    pub history: ProcessHistory,
+    /// Latest system-wide metrics (None until the first tick).
+    pub system: Option<SystemSnapshot>,
     /// Refresh interval in milliseconds.
     pub refresh_interval_ms: u64,
     /// Whether auto-refresh is paused.
     pub paused: bool,
+    /// How long `KillMode::Graceful` waits after SIGTERM before sending SIGKILL.
+    pub kill_grace_period_ms: u64,
+    /// Children started by `spawn_process` (PID → start time), until reaped.
+    pub launched: HashMap<u32, Option<u64>>,
+    /// Processes kill-type actions refuse to signal unless forced.
+    pub protection: ProtectionList,
+    /// Where actions against processes are recorded (None until the app data dir is known).
+    pub audit: Option<AuditLog>,
     /// Current OS username (cached at startup).
     pub current_user: String,
+    /// Procfs root commands read process data from (default `/proc`).
+    #[cfg(target_os = "linux")]
+    pub proc_root: PathBuf,
 }
 
 impl AppState {
     pub fn new(current_user: String) -> Self {
         Self {
             snapshot: HashMap::new(),
+            history: ProcessHistory::default(),
+            system: None,
             refresh_interval_ms: 1000,
             paused: false,
+            kill_grace_period_ms: 5000,
+            launched: HashMap::new(),
+            protection: ProtectionList::default(),
+            audit: None,
             current_user,
+            #[cfg(target_os = "linux")]
+            proc_root: PathBuf::from(crate::collector::PROC_ROOT),
         }
     }
+
+    /// Set `launched_by_app` on the processes of a fresh snapshot that we started.
+    pub fn tag_launched(&self, snapshot: &mut HashMap<u32, ProcessDto>) {
+        for (pid, start_time) in &self.launched {
+            if let Some(p) = snapshot.get_mut(pid) {
+                p.launched_by_app = start_time.is_none() || *start_time == p.start_time;
+            }
+        }
+    }
+
+    /// Fill in `status_since_ms` on a fresh snapshot: carried over from the
+    /// current snapshot while a process keeps its status, `timestamp_ms` when
+    /// the status changed or the process is new.
+    pub fn track_status(&self, snapshot: &mut HashMap<u32, ProcessDto>, timestamp_ms: u64) {
+        for (pid, p) in snapshot.iter_mut() {
+            let since = self
+                .snapshot
+                .get(pid)
+                .filter(|old| old.start_time == p.start_time && old.status == p.status)
+                .and_then(|old| old.status_since_ms);
+            p.status_since_ms = Some(since.unwrap_or(timestamp_ms));
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ProcessStatus;
+
+    fn make_proc(pid: u32, status: ProcessStatus, start_time: u64) -> ProcessDto {
+        ProcessDto {
+            status,
+            start_time: Some(start_time),
+            ..ProcessDto::test(pid, "worker")
+        }
+    }
+
+    fn tick(state: &mut AppState, procs: Vec<ProcessDto>, timestamp_ms: u64) {
+        let mut snapshot: HashMap<u32, ProcessDto> =
+            procs.into_iter().map(|p| (p.pid, p)).collect();
+        state.track_status(&mut snapshot, timestamp_ms);
+        state.snapshot = snapshot;
+    }
+
+    #[test]
+    fn test_track_status() {
+        let mut state = AppState::new("alice".to_string());
+        tick(
+            &mut state,
+            vec![
+                make_proc(1, ProcessStatus::DiskSleep, 100),
+                make_proc(2, ProcessStatus::Sleeping, 100),
+            ],
+            1_000,
+        );
+        tick(
+            &mut state,
+            vec![
+                make_proc(1, ProcessStatus::DiskSleep, 100),
+                make_proc(2, ProcessStatus::Running, 100),
+            ],
+            2_000,
+        );
+        assert_eq!(state.snapshot[&1].status_since_ms, Some(1_000));
+        assert_eq!(state.snapshot[&2].status_since_ms, Some(2_000));
+
+        // A reused PID starts over.
+        tick(
+            &mut state,
+            vec![make_proc(1, ProcessStatus::DiskSleep, 500)],
+            3_000,
+        );
+        assert_eq!(state.snapshot[&1].status_since_ms, Some(3_000));
+    }
 }
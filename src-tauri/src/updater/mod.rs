@@ -15,24 +15,35 @@ use tracing::{error, info};
 // Tauri's own managed runtime, which is always ready during setup.
 
 use crate::{
-    collector::SysinfoCollector,
+    collector::{ProcessCollector, SystemCollector},
     models::{ProcessDto, ProcessUpdateEvent},
     state::AppState,
 };
 
 pub const EVENT_PROCESSES_UPDATE: &str = "processes:update";
 pub const EVENT_PROCESS_GONE: &str = "process:gone";
+pub const EVENT_SYSTEM_UPDATE: &str = "system:update";
 
 /// Starts the background refresh loop in a Tokio task.
 /// Interval is read from AppState on each tick to support live changes.
-pub fn start_updater(app_handle: AppHandle, state: Arc<Mutex<AppState>>) {
+pub fn start_updater(
+    app_handle: AppHandle,
+    state: Arc<Mutex<AppState>>,
+    mut collector: Box<dyn ProcessCollector>,
+) {
     tauri::async_runtime::spawn(async move {
-        let mut collector = SysinfoCollector::new();
+        let mut system_collector = SystemCollector::new();
 
         // Initial snapshot
-        let initial = collector.collect();
+        let mut initial = collector.collect();
+        let timestamp_ms = now_ms();
+        let system = system_collector.collect(&initial, timestamp_ms);
         {
             let mut s = state.lock();
+            s.tag_launched(&mut initial);
+            s.track_status(&mut initial, timestamp_ms);
+            s.history.record(&initial, timestamp_ms);
+            s.system = Some(system);
             s.snapshot = initial;
         }
 
@@ -51,10 +62,16 @@ pub fn start_updater(app_handle: AppHandle, state: Arc<Mutex<AppState>>) {
                 continue;
             }
 
-            let new_snapshot = collector.collect();
+            let mut new_snapshot = collector.collect();
+            let timestamp_ms = now_ms();
+            let system = system_collector.collect(&new_snapshot, timestamp_ms);
             let event = {
                 let mut s = state.lock();
-                let event = diff_snapshots(&s.snapshot, &new_snapshot);
+                s.tag_launched(&mut new_snapshot);
+                s.track_status(&mut new_snapshot, timestamp_ms);
+                let event = diff_snapshots(&s.snapshot, &new_snapshot, timestamp_ms);
+                s.history.record(&new_snapshot, timestamp_ms);
+                s.system = Some(system.clone());
                 s.snapshot = new_snapshot;
                 event
             };
@@ -65,6 +82,10 @@ pub fn start_updater(app_handle: AppHandle, state: Arc<Mutex<AppState>>) {
                     error!("Failed to emit processes:update: {}", e);
                 }
             }
+
+            if let Err(e) = app_handle.emit(EVENT_SYSTEM_UPDATE, &system) {
+                error!("Failed to emit system:update: {}", e);
+            }
         }
     });
 }
@@ -73,6 +94,7 @@ pub fn start_updater(app_handle: AppHandle, state: Arc<Mutex<AppState>>) {
 fn diff_snapshots(
     old: &HashMap<u32, ProcessDto>,
     new: &HashMap<u32, ProcessDto>,
+    timestamp_ms: u64,
 ) -> ProcessUpdateEvent {
     let mut added = Vec::new();
     let mut updated = Vec::new();
@@ -97,11 +119,6 @@ fn diff_snapshots(
         }
     }
 
-    let timestamp_ms = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_millis() as u64)
-        .unwrap_or(0);
-
     ProcessUpdateEvent {
         added,
         updated,
@@ -110,10 +127,24 @@ fn diff_snapshots(
     }
 }
 
+/// Current wall-clock time in Unix milliseconds.
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
 /// Check if relevant fields changed enough to warrant an update notification.
 fn is_changed(old: &ProcessDto, new: &ProcessDto) -> bool {
     // Use a small epsilon for floating-point comparison
     (old.cpu_percent - new.cpu_percent).abs() > 0.1
         || old.memory_bytes != new.memory_bytes
         || old.status != new.status
+        || old.threads != new.threads
+        || old.nice != new.nice
+        || old.scheduling_policy != new.scheduling_policy
+        || old.oom_score != new.oom_score
+        || old.oom_score_adj != new.oom_score_adj
+        || old.launched_by_app != new.launched_by_app
 }
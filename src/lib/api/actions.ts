@@ -1,8 +1,152 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { AppError, KillMode } from '$lib/types';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type {
+  AppError,
+  AuditEntry,
+  AuditQuery,
+  IoPriorityClass,
+  KillMatchingResult,
+  KillMode,
+  KillProgressEvent,
+  ProcessActionResult,
+  ProcessFilter,
+  ProtectionEntry,
+  ProtectionRule,
+  RlimitResource,
+  SignalSpec,
+  SpawnOptions,
+  TreeKillOrder,
+} from '$lib/types';
 
-export async function killProcess(pid: number, mode: KillMode): Promise<void> {
-  return invoke('kill_process', { pid, mode });
+// `startTime` is the `start_time` the user saw; the backend refuses with
+// `IdentityMismatch` if the PID has since been reused by another process.
+// `force` overrides the protection list, which otherwise fails with `Protected`.
+
+export async function killProcess(
+  pid: number,
+  mode: KillMode,
+  startTime?: number | null,
+  force?: boolean
+): Promise<void> {
+  return invoke('kill_process', { pid, mode, startTime, force });
+}
+
+export async function killTree(
+  pid: number,
+  mode: KillMode,
+  order?: TreeKillOrder,
+  startTime?: number | null,
+  force?: boolean
+): Promise<ProcessActionResult[]> {
+  return invoke<ProcessActionResult[]>('kill_tree', { pid, mode, order, startTime, force });
+}
+
+export async function killMatching(
+  filter: ProcessFilter,
+  mode: KillMode,
+  dryRun: boolean,
+  force?: boolean
+): Promise<KillMatchingResult> {
+  return invoke<KillMatchingResult>('kill_matching', { filter, mode, dryRun, force });
+}
+
+export async function sendSignal(
+  pid: number,
+  signal: SignalSpec,
+  startTime?: number | null,
+  force?: boolean
+): Promise<void> {
+  return invoke('send_signal', { pid, signal, startTime, force });
+}
+
+export async function suspendProcess(
+  pid: number,
+  startTime?: number | null,
+  force?: boolean
+): Promise<void> {
+  return invoke('suspend_process', { pid, startTime, force });
+}
+
+export async function resumeProcess(pid: number, startTime?: number | null): Promise<void> {
+  return invoke('resume_process', { pid, startTime });
+}
+
+export async function setPriority(
+  pid: number,
+  nice: number,
+  startTime?: number | null
+): Promise<void> {
+  return invoke('set_priority', { pid, nice, startTime });
+}
+
+export async function setAffinity(
+  pid: number,
+  cpus: number[],
+  allThreads?: boolean,
+  startTime?: number | null
+): Promise<void> {
+  return invoke('set_affinity', { pid, cpus, allThreads, startTime });
+}
+
+export async function setIoPriority(
+  pid: number,
+  ioClass: IoPriorityClass,
+  level: number,
+  startTime?: number | null
+): Promise<void> {
+  return invoke('set_io_priority', { pid, class: ioClass, level, startTime });
+}
+
+export async function setRlimit(
+  pid: number,
+  resource: RlimitResource,
+  soft: number | null,
+  hard: number | null,
+  startTime?: number | null
+): Promise<void> {
+  return invoke('set_rlimit', { pid, resource, soft, hard, startTime });
+}
+
+export async function setOomScoreAdj(
+  pid: number,
+  value: number,
+  startTime?: number | null
+): Promise<void> {
+  return invoke('set_oom_score_adj', { pid, value, startTime });
+}
+
+export async function spawnProcess(options: SpawnOptions): Promise<number> {
+  return invoke<number>('spawn_process', { options });
+}
+
+export async function restartProcess(
+  pid: number,
+  startTime?: number | null,
+  force?: boolean
+): Promise<number> {
+  return invoke<number>('restart_process', { pid, startTime, force });
+}
+
+export async function setKillGracePeriod(ms: number): Promise<void> {
+  return invoke('set_kill_grace_period', { ms });
+}
+
+export async function listProtectionRules(): Promise<ProtectionEntry[]> {
+  return invoke<ProtectionEntry[]>('list_protection_rules');
+}
+
+export async function setProtectionRules(rules: ProtectionRule[]): Promise<void> {
+  return invoke('set_protection_rules', { rules });
+}
+
+export async function listAuditLog(query?: AuditQuery): Promise<AuditEntry[]> {
+  return invoke<AuditEntry[]>('list_audit_log', { query });
+}
+
+export async function onKillProgress(
+  handler: (event: KillProgressEvent) => void
+): Promise<UnlistenFn> {
+  return listen<KillProgressEvent>('kill:progress', (e) => handler(e.payload));
 }
 
 export async function openPath(pid: number): Promise<void> {
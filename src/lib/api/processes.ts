@@ -3,20 +3,37 @@ import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import type {
   ProcessDto,
   ProcessDetails,
+  HistorySample,
   ProcessFilter,
+  ProcessTreeNode,
   SortSpec,
   ProcessUpdateEvent,
+  SystemSnapshot,
 } from '$lib/types';
 
 export async function listProcesses(
   filter?: ProcessFilter,
-  sort?: SortSpec
+  sort?: SortSpec[]
 ): Promise<ProcessDto[]> {
   return invoke<ProcessDto[]>('list_processes', { filter, sort });
 }
 
-export async function processDetails(pid: number): Promise<ProcessDetails> {
-  return invoke<ProcessDetails>('process_details', { pid });
+export async function processTree(
+  filter?: ProcessFilter,
+  sort?: SortSpec[]
+): Promise<ProcessTreeNode[]> {
+  return invoke<ProcessTreeNode[]>('process_tree', { filter, sort });
+}
+
+export async function processDetails(
+  pid: number,
+  threadAffinity?: boolean
+): Promise<ProcessDetails> {
+  return invoke<ProcessDetails>('process_details', { pid, threadAffinity });
+}
+
+export async function processHistory(pid: number, windowSecs?: number): Promise<HistorySample[]> {
+  return invoke<HistorySample[]>('process_history', { pid, windowSecs });
 }
 
 export async function setRefreshInterval(ms: number): Promise<void> {
@@ -27,8 +44,18 @@ export async function setPaused(paused: boolean): Promise<void> {
   return invoke('set_paused', { paused });
 }
 
+export async function systemOverview(): Promise<SystemSnapshot | null> {
+  return invoke<SystemSnapshot | null>('system_overview');
+}
+
 export async function onProcessesUpdate(
   handler: (event: ProcessUpdateEvent) => void
 ): Promise<UnlistenFn> {
   return listen<ProcessUpdateEvent>('processes:update', (e) => handler(e.payload));
 }
+
+export async function onSystemUpdate(
+  handler: (snapshot: SystemSnapshot) => void
+): Promise<UnlistenFn> {
+  return listen<SystemSnapshot>('system:update', (e) => handler(e.payload));
+}
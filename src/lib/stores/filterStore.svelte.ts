@@ -1,4 +1,4 @@
-import type { ProcessFilter, SortSpec, SortField, SortDirection } from '$lib/types';
+import type { ProcessFilter, SearchMode, SortSpec, SortField, SortDirection } from '$lib/types';
 
 // ────────────────────────────────────────────────────────────────────────────
 // Filter + sort store (Svelte 5 runes)
@@ -6,6 +6,7 @@ import type { ProcessFilter, SortSpec, SortField, SortDirection } from '$lib/typ
 
 function createFilterStore() {
   let search = $state('');
+  let searchMode = $state<SearchMode>('substring');
   let mineOnly = $state(false);
   let systemOnly = $state(false);
   let nonSystemOnly = $state(false);
@@ -16,7 +17,10 @@ function createFilterStore() {
 
   function toFilter(): ProcessFilter {
     const f: ProcessFilter = {};
-    if (search.trim()) f.search = search.trim();
+    if (search.trim()) {
+      f.search = search.trim();
+      f.search_mode = searchMode;
+    }
     if (mineOnly) f.mine_only = true;
     if (systemOnly) f.system_only = true;
     if (nonSystemOnly) f.non_system_only = true;
@@ -52,6 +56,8 @@ function createFilterStore() {
   return {
     get search() { return search; },
     set search(v: string) { search = v; },
+    get searchMode() { return searchMode; },
+    set searchMode(v: SearchMode) { searchMode = v; },
     get mineOnly() { return mineOnly; },
     set mineOnly(v: boolean) { mineOnly = v; },
     get systemOnly() { return systemOnly; },
@@ -1,21 +1,49 @@
 // Shared TypeScript types mirroring Rust DTOs
 
-export type ProcessStatus = 'running' | 'sleeping' | 'stopped' | 'zombie' | 'unknown';
+export type ProcessStatus =
+  | 'running'
+  | 'sleeping'
+  | 'idle'
+  | 'disk_sleep'
+  | 'stopped'
+  | 'tracing_stop'
+  | 'zombie'
+  | 'dead'
+  | 'wakekill'
+  | 'parked'
+  | 'lock_blocked'
+  | 'unknown';
 
 export interface ProcessDto {
   pid: number;
   name: string;
   status: ProcessStatus;
+  /** Unix ms when the process was first seen in its current status. */
+  status_since_ms: number | null;
   cpu_percent: number;
   memory_bytes: number;
   user: string | null;
   path: string | null;
+  cwd: string | null;
   parent_pid: number | null;
   start_time: number | null;
   needs_elevation: boolean;
+  kind: ProcessKind;
   cmd: string[];
+  threads: number | null;
+  disk_read_bytes: number | null;
+  disk_written_bytes: number | null;
+  nice: number | null;
+  scheduling_policy: SchedulingPolicy | null;
+  oom_score: number | null;
+  oom_score_adj: number | null;
+  launched_by_app: boolean;
 }
 
+export type ProcessKind = 'kernel' | 'system' | 'user' | 'unknown';
+
+export type SchedulingPolicy = 'other' | 'batch' | 'idle' | 'fifo' | 'round_robin' | 'deadline';
+
 export interface ProcessDetails {
   dto: ProcessDto;
   threads: number | null;
@@ -24,9 +52,96 @@ export interface ProcessDetails {
   disk_written_bytes: number | null;
   open_files_count: number | null;
   environment: string[] | null;
+  realtime_priority: number | null;
+  resource_limits: ResourceLimit[] | null;
+  io_priority: IoPriority | null;
+  cpu_affinity: number[] | null;
+  thread_affinity: ThreadAffinity[] | null;
+  restricted: DetailField[];
+}
+
+export type RlimitResource =
+  | 'cpu'
+  | 'fsize'
+  | 'data'
+  | 'stack'
+  | 'core'
+  | 'rss'
+  | 'nproc'
+  | 'nofile'
+  | 'memlock'
+  | 'as'
+  | 'locks'
+  | 'sigpending'
+  | 'msgqueue'
+  | 'nice'
+  | 'rtprio'
+  | 'rttime';
+
+/** `soft` / `hard` are null when unlimited. */
+export interface ResourceLimit {
+  resource: RlimitResource;
+  soft: number | null;
+  hard: number | null;
+  unit: string | null;
+}
+
+export type IoPriorityClass = 'none' | 'realtime' | 'best_effort' | 'idle';
+
+export interface IoPriority {
+  class: IoPriorityClass;
+  level: number;
+}
+
+export interface ThreadAffinity {
+  tid: number;
+  name: string;
+  cpus: number[];
+}
+
+export type DetailField =
+  | 'threads'
+  | 'virtual_memory_bytes'
+  | 'disk_read_bytes'
+  | 'disk_written_bytes'
+  | 'open_files_count'
+  | 'environment'
+  | 'resource_limits';
+
+export interface ProcessTreeNode {
+  process: ProcessDto;
+  children: ProcessTreeNode[];
+  subtree_cpu_percent: number;
+  subtree_memory_bytes: number;
+  descendant_count: number;
+}
+
+export interface HistorySample {
+  timestamp_ms: number;
+  cpu_percent: number;
+  memory_bytes: number;
+  disk_read_bytes_per_sec: number | null;
+  disk_written_bytes_per_sec: number | null;
+  threads: number | null;
 }
 
-export type SortField = 'pid' | 'name' | 'cpu_percent' | 'memory_bytes' | 'user' | 'status' | 'start_time';
+export type SortField =
+  | 'pid'
+  | 'name'
+  | 'cpu_percent'
+  | 'memory_bytes'
+  | 'user'
+  | 'status'
+  | 'start_time'
+  | 'oom_score'
+  | 'parent_pid'
+  | 'path'
+  | 'cmd'
+  | 'threads'
+  | 'nice'
+  | 'disk_read_bytes'
+  | 'disk_written_bytes'
+  | 'age';
 export type SortDirection = 'asc' | 'desc';
 
 export interface SortSpec {
@@ -34,8 +149,11 @@ export interface SortSpec {
   direction: SortDirection;
 }
 
+export type SearchMode = 'substring' | 'regex' | 'glob' | 'exact';
+
 export interface ProcessFilter {
   search?: string;
+  search_mode?: SearchMode;
   user?: string;
   mine_only?: boolean;
   system_only?: boolean;
@@ -43,9 +161,98 @@ export interface ProcessFilter {
   status?: ProcessStatus;
   cpu_gt?: number;
   memory_gt_bytes?: number;
+  /** Expression such as `cpu > 20 and (name ~ /java/ or cmd contains "--port")` */
+  query?: string;
+}
+
+export type KillMode = 'terminate' | 'kill' | 'graceful';
+
+export type KillStage =
+  | 'terminate_sent'
+  | 'kill_sent'
+  | 'exited_after_terminate'
+  | 'exited_after_kill'
+  | 'still_running'
+  | 'failed';
+
+export interface KillProgressEvent {
+  pid: number;
+  stage: KillStage;
+  elapsed_ms: number;
+  grace_period_ms: number;
+  error: AppError | null;
+}
+
+/** Signal name (`'SIGHUP'`, `'usr1'`) or number. */
+export type SignalSpec = string | number;
+
+export type TreeKillOrder = 'children_first' | 'parent_first';
+
+/** `env` values of null remove the variable from the child's environment. */
+export interface SpawnOptions {
+  program: string;
+  arg0?: string | null;
+  args?: string[];
+  cwd?: string | null;
+  env?: Record<string, string | null>;
+  clear_env?: boolean;
+  nice?: number | null;
+  stdout_path?: string | null;
+  stderr_path?: string | null;
+}
+
+export type ProtectionRule =
+  | { kind: 'pid'; value: number }
+  | { kind: 'name'; value: string }
+  | { kind: 'path'; value: string }
+  | { kind: 'user'; value: string };
+
+export interface ProtectionEntry {
+  rule: ProtectionRule;
+  builtin: boolean;
+  reason: string;
+  /** Start time of the process a built-in PID rule was made for. */
+  start_time: number | null;
+}
+
+export type AuditOutcome = { status: 'success' } | { status: 'failed'; error: AppError };
+
+export interface AuditEntry {
+  timestamp_ms: number;
+  actor: string;
+  pid: number;
+  start_time: number | null;
+  name: string | null;
+  cmd: string[];
+  action: string;
+  detail: string;
+  outcome: AuditOutcome;
+}
+
+/** Entries are returned newest first; `limit` defaults to 500. */
+export interface AuditQuery {
+  pid?: number;
+  action?: string;
+  actor?: string;
+  since_ms?: number;
+  limit?: number;
+}
+
+export interface ProcessActionResult {
+  pid: number;
+  name: string;
+  error: AppError | null;
+}
+
+export interface KillCandidate {
+  process: ProcessDto;
+  /** Why the process would be skipped; null if it would be signalled. */
+  protected: string | null;
 }
 
-export type KillMode = 'terminate' | 'kill';
+export type KillMatchingResult =
+  | { kind: 'dry_run'; candidates: KillCandidate[] }
+  | { kind: 'executed'; results: ProcessActionResult[] };
 
 export interface ProcessUpdateEvent {
   added: ProcessDto[];
@@ -54,9 +261,62 @@ export interface ProcessUpdateEvent {
   timestamp_ms: number;
 }
 
+export interface LoadAverage {
+  one: number;
+  five: number;
+  fifteen: number;
+}
+
+export interface PressureAverages {
+  avg10: number;
+  avg60: number;
+  avg300: number;
+  total_us: number;
+}
+
+export interface PressureStall {
+  some: PressureAverages;
+  full: PressureAverages | null;
+}
+
+export interface SystemPressure {
+  cpu: PressureStall | null;
+  memory: PressureStall | null;
+  io: PressureStall | null;
+}
+
+export interface SystemSnapshot {
+  timestamp_ms: number;
+  cpu_percent: number;
+  per_core_cpu_percent: number[];
+  memory_total_bytes: number;
+  memory_used_bytes: number;
+  memory_available_bytes: number;
+  memory_cached_bytes: number | null;
+  swap_total_bytes: number;
+  swap_used_bytes: number;
+  load_average: LoadAverage;
+  uptime_secs: number;
+  process_count: number;
+  thread_count: number | null;
+  pressure: SystemPressure | null;
+}
+
 export type AppError =
   | { type: 'NotFound'; data: { pid: number } }
   | { type: 'PermissionDenied'; data: { pid: number; message: string } }
   | { type: 'InvalidPid'; data: { pid: number } }
   | { type: 'OsError'; data: { message: string } }
-  | { type: 'Unsupported'; data: { feature: string } };
+  | { type: 'Unsupported'; data: { feature: string } }
+  | { type: 'InvalidSignal'; data: { signal: string } }
+  | { type: 'InvalidPriority'; data: { value: number } }
+  | { type: 'InvalidAffinity'; data: { message: string } }
+  | { type: 'InvalidLimit'; data: { message: string } }
+  | { type: 'SpawnFailed'; data: { program: string; message: string } }
+  | { type: 'InvalidFilter'; data: { message: string } }
+  | { type: 'InvalidRegex'; data: { pattern: string; message: string } }
+  | { type: 'Protected'; data: { pid: number; reason: string } }
+  | {
+      type: 'IdentityMismatch';
+      data: { pid: number; expected_start_time: number; actual_start_time: number };
+    };
@@ -0,0 +1,76 @@
+import { describe, it, expect } from 'vitest';
+import type { ProcessDto } from '$lib/types';
+import { buildSearchMatcher, globToRegExp } from './search';
+
+function proc(overrides: Partial<ProcessDto>): ProcessDto {
+  return {
+    pid: 42,
+    name: 'python3',
+    status: 'running',
+    status_since_ms: null,
+    cpu_percent: 0,
+    memory_bytes: 0,
+    user: 'alice',
+    path: '/usr/bin/python3',
+    cwd: null,
+    parent_pid: null,
+    start_time: null,
+    needs_elevation: false,
+    kind: 'user',
+    cmd: [],
+    threads: null,
+    disk_read_bytes: null,
+    disk_written_bytes: null,
+    nice: null,
+    scheduling_policy: null,
+    oom_score: null,
+    oom_score_adj: null,
+    launched_by_app: false,
+    ...overrides,
+  };
+}
+
+const server = proc({ cmd: ['python3', 'manage.py', 'runserver'], cwd: '/srv/site' });
+const other = proc({ pid: 43 });
+
+describe('globToRegExp', () => {
+  it('anchors and translates wildcards', () => {
+    expect(globToRegExp('*.py')).toBe('^.*\\.py$');
+    expect(globToRegExp('a?c')).toBe('^a.c$');
+  });
+
+  it('keeps bracket classes and negates with !', () => {
+    expect(globToRegExp('[!a-z]x')).toBe('^[^a-z]x$');
+  });
+
+  it('treats a leading ] in a class as a literal', () => {
+    expect(globToRegExp('[]a]')).toBe('^[\\]a]$');
+    expect(globToRegExp('[!]]')).toBe('^[^\\]]$');
+    expect(new RegExp(globToRegExp('x[]]')).test('x]')).toBe(true);
+  });
+});
+
+describe('buildSearchMatcher', () => {
+  it('substring matches command-line arguments and cwd', () => {
+    const m = buildSearchMatcher('Manage.py Runserver', 'substring')!;
+    expect(m(server)).toBe(true);
+    expect(m(other)).toBe(false);
+    expect(buildSearchMatcher('/srv', 'substring')!(server)).toBe(true);
+  });
+
+  it('regex and glob modes match any field', () => {
+    expect(buildSearchMatcher('manage\\.py\\s+run', 'regex')!(server)).toBe(true);
+    expect(buildSearchMatcher('python3 *.py run*', 'glob')!(server)).toBe(true);
+    expect(buildSearchMatcher('python3 *.py run*', 'glob')!(other)).toBe(false);
+  });
+
+  it('exact compares whole fields, case included', () => {
+    expect(buildSearchMatcher('manage.py', 'exact')!(server)).toBe(true);
+    expect(buildSearchMatcher('MANAGE.PY', 'exact')!(server)).toBe(false);
+    expect(buildSearchMatcher('manage', 'exact')!(server)).toBe(false);
+  });
+
+  it('returns null for an invalid regex', () => {
+    expect(buildSearchMatcher('(unclosed', 'regex')).toBeNull();
+  });
+});
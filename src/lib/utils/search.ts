@@ -0,0 +1,83 @@
+import type { ProcessDto, SearchMode } from '$lib/types';
+
+/**
+ * Escapes a string for literal use inside a RegExp.
+ */
+function escapeRegExp(s: string): string {
+  return s.replace(/[.*+?^${}()|[\]\\]/g, '\\$&');
+}
+
+/**
+ * Translates a shell glob (`*`, `?`, `[abc]`, `[!abc]`) into an anchored
+ * RegExp source, mirroring the backend's glob search mode. A `]` right after
+ * `[` or `[!` is a literal, as in fnmatch.
+ */
+export function globToRegExp(glob: string): string {
+  let out = '^';
+  let inClass = false;
+  for (let i = 0; i < glob.length; i++) {
+    const c = glob[i];
+    if (inClass) {
+      if (c === ']') inClass = false;
+      else if (c === '\\' || c === '[') out += '\\';
+      out += c;
+      continue;
+    }
+    if (c === '*') out += '.*';
+    else if (c === '?') out += '.';
+    else if (c === '[') {
+      inClass = true;
+      out += '[';
+      if (glob[i + 1] === '!') {
+        out += '^';
+        i++;
+      }
+      if (glob[i + 1] === ']') {
+        out += '\\]';
+        i++;
+      }
+    } else out += escapeRegExp(c);
+  }
+  return out + '$';
+}
+
+/**
+ * Fields the search box matches against: name, path, PID, user, cwd, the
+ * full command line and each of its arguments.
+ */
+function searchFields(p: ProcessDto): string[] {
+  const fields = [p.name, p.pid.toString()];
+  if (p.path) fields.push(p.path);
+  if (p.user) fields.push(p.user);
+  if (p.cwd) fields.push(p.cwd);
+  if (p.cmd.length > 0) fields.push(p.cmd.join(' '), ...p.cmd);
+  return fields;
+}
+
+/**
+ * Builds a predicate for `search` in the given mode. Every mode but `exact`
+ * ignores case.
+ * Returns null when the regex or glob does not compile.
+ */
+export function buildSearchMatcher(
+  search: string,
+  mode: SearchMode,
+): ((p: ProcessDto) => boolean) | null {
+  const q = search.toLowerCase();
+  switch (mode) {
+    case 'substring':
+      return (p) => searchFields(p).some((f) => f.toLowerCase().includes(q));
+    case 'exact':
+      return (p) => searchFields(p).some((f) => f === search);
+    case 'regex':
+    case 'glob': {
+      let re: RegExp;
+      try {
+        re = new RegExp(mode === 'glob' ? globToRegExp(search) : search, 'i');
+      } catch {
+        return null;
+      }
+      return (p) => searchFields(p).some((f) => re.test(f));
+    }
+  }
+}